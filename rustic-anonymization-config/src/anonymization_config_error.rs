@@ -0,0 +1,44 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// An error raised while loading an anonymization configuration file.
+#[derive(Debug)]
+pub enum AnonymizationConfigError {
+    /// The file exists but could not be read.
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file is not a valid anonymization configuration.
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+impl Display for AnonymizationConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnonymizationConfigError::Read { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            AnonymizationConfigError::Parse { path, source } => {
+                write!(
+                    f,
+                    "could not parse {}: {}",
+                    path.display(),
+                    source.to_string().trim_end()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnonymizationConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnonymizationConfigError::Read { source, .. } => Some(source),
+            AnonymizationConfigError::Parse { source, .. } => Some(source),
+        }
+    }
+}
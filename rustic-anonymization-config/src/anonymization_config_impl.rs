@@ -1,47 +1,83 @@
-use std::path::PathBuf;
-use std::{env, fs};
-use tracing::debug;
+use rustic_config_source::config_root::ConfigRoot;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use tracing::{debug, info};
 
+use crate::anonymization_config_error::AnonymizationConfigError;
 use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::config_structs::column_transformation_struct::AnonymizationColumnTransformation;
+use crate::config_structs::name_match_type_struct::NameMatchType;
+use crate::config_structs::sampling_type_struct::SamplingType;
+use crate::config_structs::schema_drift_policy_struct::SchemaDriftPolicy;
 use crate::config_structs::table_struct::AnonymizationConfigTable;
+use crate::config_structs::table_type_struct::AnonymizationConfigTableType;
 
 impl AnonymizationConfig {
     /// Load the configuration for a specific database and schema.
     ///
     /// This method loads the configuration for a specific database and schema from a TOML file.
+    /// The `column_rules` of the shared `common.toml` file, if present, are appended to the
+    /// ones of the database and schema configuration, so the latter take precedence.
+    /// A missing file is the same as an empty configuration.
     ///
     /// # Arguments
     ///
+    /// * `config_root` - The directory containing the configuration files.
     /// * `database_name` - The name of the database.
     /// * `schema_name` - The name of the schema.
     ///
     /// # Returns
     ///
-    /// The `AnonymizationConfig` struct containing the loaded configuration.
-    pub fn load_config_for(database_name: &str, schema_name: &str) -> AnonymizationConfig {
-        let mut conf_file_path = PathBuf::new();
-        conf_file_path.push(env::current_dir().unwrap());
-        conf_file_path.push("configuration_data");
-        conf_file_path.push(format!("{database_name}-{schema_name}-sync.toml"));
+    /// The `AnonymizationConfig` struct containing the loaded configuration,
+    /// or an error if a file cannot be read or parsed.
+    pub fn load_config_for(
+        config_root: &ConfigRoot,
+        database_name: &str,
+        schema_name: &str,
+    ) -> Result<AnonymizationConfig, AnonymizationConfigError> {
+        let mut config = Self::load_config_file(
+            &config_root.anonymization_config_path(database_name, schema_name),
+        )?
+        .unwrap_or_default();
 
+        if let Some(common_config) = Self::load_config_file(&config_root.common_config_path())? {
+            config.column_rules.extend(common_config.column_rules);
+        }
+
+        Ok(config)
+    }
+
+    /// Loads a single configuration file, returning `None` if it does not exist.
+    fn load_config_file(
+        conf_file_path: &Path,
+    ) -> Result<Option<AnonymizationConfig>, AnonymizationConfigError> {
         debug!("Configuration file path: {:?}", conf_file_path.as_os_str());
 
-        let read_conf = fs::read_to_string(conf_file_path.as_os_str());
+        let conf = match fs::read_to_string(conf_file_path) {
+            Ok(conf) => conf,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(AnonymizationConfigError::Read {
+                    path: conf_file_path.to_path_buf(),
+                    source: e,
+                });
+            }
+        };
 
-        match read_conf {
-            Ok(conf) => match toml::from_str(&conf) {
-                Ok(conf) => conf,
-                Err(e) => {
-                    panic!("Error parsing configuration file: {e:?}");
-                }
-            },
-            Err(_) => AnonymizationConfig::default(),
-        }
+        toml::from_str(&conf)
+            .map(Some)
+            .map_err(|e| AnonymizationConfigError::Parse {
+                path: conf_file_path.to_path_buf(),
+                source: e,
+            })
     }
 
     /// Fetch the configuration for a specific table.
     ///
     /// This method fetches the configuration for a specific table from the loaded configuration.
+    /// A table whose `table_name` is equal to the table name wins. Otherwise the most specific
+    /// matching pattern is picked, with ties resolved by the order of the tables in the configuration.
     ///
     /// # Arguments
     ///
@@ -51,8 +87,150 @@ impl AnonymizationConfig {
     ///
     /// An `Option` containing a reference to the `AnonymizationConfigTable` if found, or `None` if not found.
     pub fn fetch_table_config(&self, table_name: &str) -> Option<&AnonymizationConfigTable> {
-        self.tables
+        let (table_config, matched_by_pattern) = self.find_table_config(table_name)?;
+
+        if matched_by_pattern {
+            info!(
+                "Table {table_name} matched table pattern `{}`",
+                table_config.table_name
+            );
+        }
+
+        Some(table_config)
+    }
+
+    /// Returns the schema drift policy of a table.
+    ///
+    /// The policy of the matching table configuration wins over the global `on_schema_drift`.
+    pub fn schema_drift_policy(&self, table_name: &str) -> &SchemaDriftPolicy {
+        self.find_table_config(table_name)
+            .and_then(|(table_config, _)| table_config.on_schema_drift.as_ref())
+            .unwrap_or(&self.on_schema_drift)
+    }
+
+    /// Finds the configuration for a specific table, along with whether it was matched by a pattern.
+    pub(crate) fn find_table_config(
+        &self,
+        table_name: &str,
+    ) -> Option<(&AnonymizationConfigTable, bool)> {
+        if let Some(table_config) = self
+            .tables
             .iter()
             .find(|table| table.table_name == table_name)
+        {
+            return Some((table_config, false));
+        }
+
+        // `max_by_key` returns the last of equally specific patterns,
+        // so iterate in reverse to keep the first declared one.
+        self.tables
+            .iter()
+            .rev()
+            .filter(|table| table.match_type.matches(&table.table_name, table_name))
+            .max_by_key(|table| table.match_type.specificity(&table.table_name))
+            .map(|table_config| (table_config, true))
+    }
+
+    /// Resolve the configuration for a specific table, given the columns it has.
+    ///
+    /// The explicit configuration of the table is extended with a transformation for every
+    /// column that matches one of the `column_rules` and is not configured explicitly.
+    /// Tables with a `Single` anonymization type are left untouched.
+    /// The `table_name` of the resolved configuration is always the specified table name,
+    /// even if it was matched by a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The name of the table.
+    /// * `column_names` - The names of the columns of the table.
+    ///
+    /// # Returns
+    ///
+    /// An `Option` containing the resolved `AnonymizationConfigTable`, or `None` if the table
+    /// is neither configured explicitly nor matched by any column rule.
+    pub fn resolve_table_config(
+        &self,
+        table_name: &str,
+        column_names: &[String],
+    ) -> Option<AnonymizationConfigTable> {
+        let table_config = self.fetch_table_config(table_name);
+
+        let explicit_columns = match table_config.map(|table| &table.anonymization_type) {
+            Some(AnonymizationConfigTableType::Single { .. }) => {
+                return table_config.map(|table_config| table_config.resolved_for(table_name));
+            }
+            Some(AnonymizationConfigTableType::Multi {
+                column_transformations,
+            }) => column_transformations
+                .iter()
+                .map(|column_transformation| column_transformation.column_name.as_str())
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+
+        let rule_transformations = column_names
+            .iter()
+            .filter(|column_name| !explicit_columns.contains(&column_name.as_str()))
+            .filter_map(|column_name| {
+                let rule = self
+                    .column_rules
+                    .iter()
+                    .find(|rule| rule.matches(column_name))?;
+                debug!(
+                    "Column {table_name}.{column_name} matched column rule `{}`",
+                    rule.pattern
+                );
+                Some(AnonymizationColumnTransformation {
+                    column_name: column_name.clone(),
+                    transformation_type: rule.transformation_type.clone(),
+                    retain_if_empty: rule.retain_if_empty,
+                    domain: rule.domain.clone(),
+                    on_null: rule.on_null,
+                    on_empty: rule.on_empty,
+                    cast: rule.cast,
+                    when: None,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let Some(table_config) = table_config else {
+            if rule_transformations.is_empty() {
+                return None;
+            }
+
+            return Some(AnonymizationConfigTable {
+                table_name: table_name.to_string(),
+                match_type: NameMatchType::Exact,
+                anonymization_type: AnonymizationConfigTableType::Multi {
+                    column_transformations: rule_transformations,
+                },
+                keep_num_of_records: None,
+                sampling: SamplingType::Head,
+                filter_type: None,
+                sanitize_null_bytes: None,
+                on_schema_drift: None,
+            });
+        };
+
+        let mut table_config = table_config.resolved_for(table_name);
+        if let AnonymizationConfigTableType::Multi {
+            column_transformations,
+        } = &mut table_config.anonymization_type
+        {
+            column_transformations.extend(rule_transformations);
+        }
+
+        Some(table_config)
+    }
+}
+
+impl AnonymizationConfigTable {
+    /// Returns a copy of the configuration for the specified concrete table name.
+    fn resolved_for(&self, table_name: &str) -> AnonymizationConfigTable {
+        AnonymizationConfigTable {
+            table_name: table_name.to_string(),
+            match_type: NameMatchType::Exact,
+            ..self.clone()
+        }
     }
 }
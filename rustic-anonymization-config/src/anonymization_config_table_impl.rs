@@ -1,114 +1,108 @@
+use toml::Table;
+
+use crate::anonymization_config_validation::ConfigProblem;
+use crate::column_transformation_impl::value_policy_problem;
+use crate::config_structs::column_transformation_struct::AnonymizationColumnTransformation;
+use crate::config_structs::filter_type_struct::FilterType;
 use crate::config_structs::table_struct::AnonymizationConfigTable;
 use crate::config_structs::table_type_struct::AnonymizationConfigTableType;
-use crate::config_structs::transformation_type_struct::AnonymizationTransformationType;
+use crate::resolved_anonymization_config::ColumnValuePolicy;
 
-use rustic_base_transformations::nulify_transformator::NullifyTransformator;
-use rustic_base_transformations::replace_transformator::ReplaceTransformator;
-use rustic_faker_transformations::faker_transformators::fake_email_with_id_prefix_transformator::FakeEmailWithIdPrefixTransformator;
-use rustic_faker_transformations::faker_transformators::fake_multi_email_transformator::FakeMultiEmailTransformator;
-use rustic_faker_transformations::faker_transformators::fake_phone_transformator::FakePhoneTransformator;
-use rustic_faker_transformations::faker_transformators::{
-    FakeAddressTransformator, FakeCompanyNameTransformator, FakeEmailTransformator,
-    FakeFirstnameTransformator, FakeLastNameTransformator, FakeMd5Transformator,
-    FakeNameTransformator,
-};
 use rustic_transformator::transformator::Transformator;
-use rustic_whole_table_transformator::whole_table_transformator::WholeTableTransformator;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
 
 /// Implementation of the `AnonymizationConfigTable` struct.
 impl AnonymizationConfigTable {
     /// Builds and returns a vector of transformators based on the `AnonymizationConfigTable` instance.
     /// The transformators are created based on the specified anonymization type and column transformations.
+    ///
+    /// Column transformators, built-in or not, are built by the transformator registry,
+    /// and so is the transformator of a `Single` transformation, with an empty column name.
     pub fn build_transformators(
         &self,
-        whole_table_transformator: impl WholeTableTransformator,
-    ) -> Vec<Box<dyn Transformator>> {
+        registry: &TransformatorRegistry,
+    ) -> Result<Vec<Box<dyn Transformator>>, ConfigProblem> {
         match &self.anonymization_type {
             AnonymizationConfigTableType::Multi {
                 column_transformations,
             } => column_transformations
                 .iter()
                 .map(|column_transformation| {
-                    let column_name = column_transformation.column_name.as_str();
-                    Self::define_transformation_type(
-                        column_name,
-                        column_transformation.transformation_type.clone(),
-                        column_transformation.retain_if_empty.unwrap_or(false),
-                    )
+                    self.build_column_transformator(registry, column_transformation)
                 })
                 .collect(),
             AnonymizationConfigTableType::Single { transformation } => {
-                vec![whole_table_transformator.transform(transformation.as_str())]
+                let transformator =
+                    registry
+                        .build(transformation, "", &Table::new())
+                        .map_err(|e| {
+                            ConfigProblem::for_table(
+                                &self.table_name,
+                                format!("`transformation` `{transformation}`: {e}"),
+                            )
+                        })?;
+                Ok(vec![transformator])
             }
         }
     }
 
-    /// Defines the transformation type based on the specified column name, transformation type, and retain if empty flag.
-    /// Returns a box containing the corresponding transformator.
-    fn define_transformation_type(
-        column_name: &str,
-        transformation_type: AnonymizationTransformationType,
-        retain_if_empty: bool,
-    ) -> Box<dyn Transformator> {
-        match transformation_type {
-            AnonymizationTransformationType::Replace { replacement_value } => {
-                Box::new(ReplaceTransformator::new(column_name, replacement_value))
-            }
-            AnonymizationTransformationType::Custom { operation_type } => {
-                Self::match_transformator(column_name, operation_type.as_str(), retain_if_empty)
-            }
-            AnonymizationTransformationType::Nullify => {
-                Box::new(NullifyTransformator::new(column_name))
-            }
+    /// Builds the transformator of a single column transformation, through the registry.
+    pub(crate) fn build_column_transformator(
+        &self,
+        registry: &TransformatorRegistry,
+        column_transformation: &AnonymizationColumnTransformation,
+    ) -> Result<Box<dyn Transformator>, ConfigProblem> {
+        let column_name = column_transformation.column_name.as_str();
+        if let Some(problem) = value_policy_problem(
+            column_transformation.retain_if_empty,
+            column_transformation.on_null,
+            column_transformation.on_empty,
+        ) {
+            return Err(ConfigProblem::for_table(
+                &self.table_name,
+                format!("column `{column_name}`: {problem}"),
+            ));
         }
+
+        let (name, params) = column_transformation
+            .transformation_type
+            .registry_entry(column_transformation.domain.as_deref());
+
+        registry.build(name, column_name, &params).map_err(|e| {
+            ConfigProblem::for_table(&self.table_name, format!("column `{column_name}`: {e}"))
+        })
     }
 
-    /// Matches the specified operation type and returns the corresponding transformator based on the specified column name and retain if empty flag.
-    fn match_transformator(
-        column_name: &str,
-        operation_type_raw: &str,
-        retain_if_empty: bool,
-    ) -> Box<dyn Transformator> {
-        match operation_type_raw {
-            "fake_phone_transformation" => Box::new(
-                FakePhoneTransformator::builder()
-                    .column_name(column_name.to_string())
-                    .build(),
-            ),
-            "fake_firstname_transformation" => Box::new(FakeFirstnameTransformator::new(
-                column_name,
-                retain_if_empty,
-            )),
-            "fake_lastname_transformation" => {
-                Box::new(FakeLastNameTransformator::new(column_name, retain_if_empty))
-            }
-            "fake_name_transformation" => {
-                Box::new(FakeNameTransformator::new(column_name, retain_if_empty))
-            }
-            "fake_email_transformation" => {
-                Box::new(FakeEmailTransformator::new(column_name, retain_if_empty))
-            }
-            "fake_multi_email_transformation" => Box::new(
-                FakeMultiEmailTransformator::builder()
-                    .column_name(column_name.to_string())
-                    .build(),
-            ),
-            "fake_companyname_transformation" => Box::new(FakeCompanyNameTransformator::new(
-                column_name,
-                retain_if_empty,
-            )),
-            "fake_address_transformation" => {
-                Box::new(FakeAddressTransformator::new(column_name, retain_if_empty))
-            }
-            "fake_md5_transformation" => {
-                Box::new(FakeMd5Transformator::new(column_name, retain_if_empty))
-            }
-            "fake_email_with_id_prefix_transformation" => Box::new(
-                FakeEmailWithIdPrefixTransformator::builder()
-                    .column_name(column_name.to_string())
-                    .build(),
-            ),
-            _ => panic!("Unknown operation type: {operation_type_raw}"),
+    /// Returns whether the records of the table are reduced, when record reduction is enabled.
+    pub fn reduces_records(&self) -> bool {
+        self.keep_num_of_records.is_some() || !self.sampling.requires_num_of_records()
+    }
+
+    /// Returns the `when` predicate of every transformator built by `build_transformators`,
+    /// in the same order. `None` means that every row is transformed.
+    pub fn transformator_conditions(&self) -> Vec<Option<FilterType>> {
+        match &self.anonymization_type {
+            AnonymizationConfigTableType::Multi {
+                column_transformations,
+            } => column_transformations
+                .iter()
+                .map(|column_transformation| column_transformation.when.clone())
+                .collect(),
+            AnonymizationConfigTableType::Single { .. } => vec![None],
+        }
+    }
+
+    /// Returns what happens to the `NULL` and empty values of the column of every transformator
+    /// built by `build_transformators`, in the same order. `None` for whole table transformators.
+    pub fn transformator_value_policies(&self) -> Vec<Option<ColumnValuePolicy>> {
+        match &self.anonymization_type {
+            AnonymizationConfigTableType::Multi {
+                column_transformations,
+            } => column_transformations
+                .iter()
+                .map(|column_transformation| Some(column_transformation.value_policy()))
+                .collect(),
+            AnonymizationConfigTableType::Single { .. } => vec![None],
         }
     }
 }
@@ -0,0 +1,481 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use regex::Regex;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
+
+use crate::column_transformation_impl::value_policy_problem;
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::config_structs::filter_type_struct::FilterType;
+use crate::config_structs::sampling_type_struct::SamplingType;
+use crate::config_structs::schema_drift_policy_struct::SchemaDriftPolicy;
+use crate::config_structs::table_struct::AnonymizationConfigTable;
+use crate::config_structs::table_type_struct::AnonymizationConfigTableType;
+
+/// A problem found while validating an anonymization configuration.
+#[derive(Debug, PartialEq)]
+pub struct ConfigProblem {
+    /// The table the problem refers to, if any.
+    pub table_name: Option<String>,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    pub(crate) fn for_table(table_name: &str, message: impl Into<String>) -> Self {
+        Self {
+            table_name: Some(table_name.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ConfigProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.table_name {
+            Some(table_name) => write!(f, "table `{table_name}`: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigProblem {}
+
+impl AnonymizationConfig {
+    /// Validates the configuration without touching any database or S3 bucket.
+    ///
+    /// Checks filter definitions, table and column rule patterns and duplicate table or column entries.
+    /// Operation names are already checked while parsing the configuration.
+    ///
+    /// # Returns
+    ///
+    /// All the problems found, in the order of the tables in the configuration.
+    /// An empty `Vec` means that the configuration is valid.
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+        let mut seen_tables = HashSet::new();
+
+        for table in &self.tables {
+            if table.table_name.trim().is_empty() {
+                problems.push(ConfigProblem {
+                    table_name: None,
+                    message: "found a table entry with an empty `table_name`".to_string(),
+                });
+            } else if !seen_tables.insert(table.table_name.as_str()) {
+                problems.push(ConfigProblem::for_table(
+                    &table.table_name,
+                    "is configured more than once",
+                ));
+            }
+
+            problems.extend(table.validate());
+        }
+
+        problems.extend(
+            self.on_schema_drift
+                .validate()
+                .into_iter()
+                .map(|message| ConfigProblem {
+                    table_name: None,
+                    message,
+                }),
+        );
+
+        for rule in &self.column_rules {
+            if rule.pattern.trim().is_empty() {
+                problems.push(ConfigProblem {
+                    table_name: None,
+                    message: "found a column rule with an empty `pattern`".to_string(),
+                });
+            } else if let Some(e) = rule.match_type.pattern_error(&rule.pattern) {
+                problems.push(ConfigProblem {
+                    table_name: None,
+                    message: format!(
+                        "column rule `{}` has an invalid `pattern`: {e}",
+                        rule.pattern
+                    ),
+                });
+            }
+        }
+
+        problems
+    }
+
+    /// Builds every configured column transformator and column rule through the registry,
+    /// without touching any database or S3 bucket.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - The transformators that can be referred to by name.
+    ///
+    /// # Returns
+    ///
+    /// A problem for every transformator that is not registered or whose parameters are invalid.
+    pub fn validate_transformators(&self, registry: &TransformatorRegistry) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+
+        for table in &self.tables {
+            match &table.anonymization_type {
+                AnonymizationConfigTableType::Multi {
+                    column_transformations,
+                } => problems.extend(column_transformations.iter().filter_map(
+                    |column_transformation| {
+                        table
+                            .build_column_transformator(registry, column_transformation)
+                            .err()
+                    },
+                )),
+                AnonymizationConfigTableType::Single { .. } => {
+                    problems.extend(table.build_transformators(registry).err());
+                }
+            }
+        }
+
+        for rule in &self.column_rules {
+            if let Some(problem) =
+                value_policy_problem(rule.retain_if_empty, rule.on_null, rule.on_empty)
+            {
+                problems.push(ConfigProblem {
+                    table_name: None,
+                    message: format!("column rule `{}`: {problem}", rule.pattern),
+                });
+            }
+            let (name, params) = rule
+                .transformation_type
+                .registry_entry(rule.domain.as_deref());
+            if let Err(e) = registry.build(name, &rule.pattern, &params) {
+                problems.push(ConfigProblem {
+                    table_name: None,
+                    message: format!("column rule `{}`: {e}", rule.pattern),
+                });
+            }
+        }
+
+        problems
+    }
+
+    /// Compares every configured table and column against the columns of the source schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_tables` - The tables of the source schema, along with their column names.
+    ///
+    /// # Returns
+    ///
+    /// A problem for every configured table that matches no table of the source schema, and for
+    /// every configured column that does not exist in a source table the configuration applies to.
+    pub fn validate_against_source(
+        &self,
+        source_tables: &HashMap<String, Vec<String>>,
+    ) -> Vec<ConfigProblem> {
+        self.tables
+            .iter()
+            .flat_map(|table| {
+                let mut matched_tables = source_tables
+                    .iter()
+                    .filter(|(source_table, _)| {
+                        self.find_table_config(source_table)
+                            .is_some_and(|(table_config, _)| std::ptr::eq(table_config, table))
+                    })
+                    .collect::<Vec<_>>();
+                matched_tables.sort_by_key(|(source_table, _)| *source_table);
+
+                if matched_tables.is_empty() {
+                    let message = if table.match_type.is_exact() {
+                        "does not exist in the source schema"
+                    } else {
+                        "does not match any table in the source schema"
+                    };
+                    return vec![ConfigProblem::for_table(&table.table_name, message)];
+                }
+
+                matched_tables
+                    .into_iter()
+                    .flat_map(|(source_table, source_columns)| {
+                        table
+                            .referenced_columns()
+                            .into_iter()
+                            .filter(|column| !source_columns.iter().any(|c| c == column))
+                            .map(|column| {
+                                ConfigProblem::for_table(
+                                    source_table,
+                                    format!("column `{column}` does not exist in the source table"),
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl AnonymizationConfigTable {
+    /// Returns the distinct columns referenced by the column transformations and their `when`
+    /// predicates, the filter and the sampling.
+    fn referenced_columns(&self) -> Vec<&str> {
+        let mut columns = vec![];
+
+        if let AnonymizationConfigTableType::Multi {
+            column_transformations,
+        } = &self.anonymization_type
+        {
+            for column_transformation in column_transformations {
+                columns.push(column_transformation.column_name.as_str());
+                if let Some(when) = &column_transformation.when {
+                    columns.extend(when.columns());
+                }
+            }
+        }
+
+        if let Some(filter) = &self.filter_type {
+            columns.extend(filter.columns());
+        }
+
+        if let SamplingType::LatestBy(column) = &self.sampling {
+            columns.push(column);
+        }
+
+        let mut seen = HashSet::new();
+        columns.retain(|column| seen.insert(*column));
+        columns
+    }
+
+    fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+
+        if let Some(e) = self.match_type.pattern_error(&self.table_name) {
+            problems.push(ConfigProblem::for_table(
+                &self.table_name,
+                format!("`table_name` is an invalid pattern: {e}"),
+            ));
+        }
+
+        if self.keep_num_of_records == Some(0) {
+            problems.push(ConfigProblem::for_table(
+                &self.table_name,
+                "`keep_num_of_records` must be greater than 0",
+            ));
+        }
+
+        if self.keep_num_of_records.is_none()
+            && !self.sampling.is_head()
+            && self.sampling.requires_num_of_records()
+        {
+            problems.push(ConfigProblem::for_table(
+                &self.table_name,
+                "`sampling` requires `keep_num_of_records`, unless it is a `percentage`",
+            ));
+        }
+
+        match &self.sampling {
+            SamplingType::LatestBy(column) if column.trim().is_empty() => {
+                problems.push(ConfigProblem::for_table(
+                    &self.table_name,
+                    "`sampling` has an empty `latest_by` column",
+                ));
+            }
+            SamplingType::Percentage(percentage)
+                if !(*percentage > 0.0 && *percentage <= 100.0) =>
+            {
+                problems.push(ConfigProblem::for_table(
+                    &self.table_name,
+                    "`sampling` percentage must be greater than 0 and at most 100",
+                ));
+            }
+            _ => {}
+        }
+
+        match &self.anonymization_type {
+            AnonymizationConfigTableType::Multi {
+                column_transformations,
+            } => {
+                let mut seen_columns = HashSet::new();
+
+                for column_transformation in column_transformations {
+                    let column_name = column_transformation.column_name.as_str();
+
+                    if column_name.trim().is_empty() {
+                        problems.push(ConfigProblem::for_table(
+                            &self.table_name,
+                            "found a column transformation with an empty `column_name`",
+                        ));
+                    } else if !seen_columns.insert(column_name) {
+                        problems.push(ConfigProblem::for_table(
+                            &self.table_name,
+                            format!("column `{column_name}` is configured more than once"),
+                        ));
+                    }
+
+                    if let Some(when) = &column_transformation.when {
+                        problems.extend(when.validate().into_iter().map(|message| {
+                            ConfigProblem::for_table(
+                                &self.table_name,
+                                format!("`when` of column `{column_name}`: {message}"),
+                            )
+                        }));
+                    }
+                }
+            }
+            AnonymizationConfigTableType::Single { transformation } => {
+                if transformation.trim().is_empty() {
+                    problems.push(ConfigProblem::for_table(
+                        &self.table_name,
+                        "`transformation` of a `Single` anonymization type must not be empty",
+                    ));
+                }
+            }
+        }
+
+        if let Some(filter) = &self.filter_type {
+            problems.extend(
+                filter
+                    .validate()
+                    .into_iter()
+                    .map(|message| ConfigProblem::for_table(&self.table_name, message)),
+            );
+        }
+
+        if let Some(policy) = &self.on_schema_drift {
+            problems.extend(
+                policy
+                    .validate()
+                    .into_iter()
+                    .map(|message| ConfigProblem::for_table(&self.table_name, message)),
+            );
+        }
+
+        problems
+    }
+}
+
+impl SchemaDriftPolicy {
+    fn validate(&self) -> Vec<String> {
+        let SchemaDriftPolicy::Rename(mapping) = self else {
+            return vec![];
+        };
+
+        if mapping.is_empty() {
+            return vec!["`on_schema_drift` rename has no columns".to_string()];
+        }
+
+        let mut problems = vec![];
+        let mut seen_targets = HashSet::new();
+
+        for (from, to) in mapping {
+            if from.trim().is_empty() || to.trim().is_empty() {
+                problems.push("`on_schema_drift` rename has an empty column name".to_string());
+            } else if !seen_targets.insert(to.as_str()) {
+                problems.push(format!(
+                    "`on_schema_drift` renames more than one column to `{to}`"
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+impl FilterType {
+    /// Returns the columns the filter is applied on, including the ones of nested filters.
+    fn columns(&self) -> Vec<&str> {
+        match self {
+            FilterType::Contains { column, .. }
+            | FilterType::StartsWith { column, .. }
+            | FilterType::EndsWith { column, .. }
+            | FilterType::StartsAndEndsWith { column, .. }
+            | FilterType::Equals { column, .. }
+            | FilterType::AnyOfInt { column, .. }
+            | FilterType::AnyOfString { column, .. }
+            | FilterType::GreaterThan { column, .. }
+            | FilterType::LessThan { column, .. }
+            | FilterType::Between { column, .. }
+            | FilterType::IsIn { column, .. }
+            | FilterType::IsNull { column, .. }
+            | FilterType::IsNotNull { column, .. }
+            | FilterType::Matches { column, .. } => vec![column],
+            FilterType::And { filters } | FilterType::Or { filters } => {
+                filters.iter().flat_map(FilterType::columns).collect()
+            }
+            FilterType::Not { filter } => filter.columns(),
+            FilterType::NoFilter | FilterType::Unrecognized => vec![],
+        }
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        match self {
+            FilterType::Contains { column, value, .. }
+            | FilterType::StartsWith { column, value, .. }
+            | FilterType::EndsWith { column, value, .. } => {
+                if value.is_empty() {
+                    problems.push(format!("filter on `{column}` has an empty `value`"));
+                }
+            }
+            FilterType::StartsAndEndsWith {
+                column,
+                start_value,
+                end_value,
+                ..
+            } => {
+                if start_value.is_empty() && end_value.is_empty() {
+                    problems.push(format!(
+                        "filter on `{column}` has both `start_value` and `end_value` empty"
+                    ));
+                }
+            }
+            FilterType::IsIn { column, values, .. } => {
+                if values.is_empty() {
+                    problems.push(format!("filter on `{column}` has no `values`"));
+                }
+            }
+            FilterType::AnyOfInt { column, values } => {
+                if values.is_empty() {
+                    problems.push(format!("filter on `{column}` has no `values`"));
+                }
+            }
+            FilterType::AnyOfString { column, values } => {
+                if values.is_empty() {
+                    problems.push(format!("filter on `{column}` has no `values`"));
+                }
+            }
+            FilterType::Matches {
+                column, pattern, ..
+            } => {
+                if let Err(e) = Regex::new(pattern) {
+                    problems.push(format!(
+                        "filter on `{column}` has an invalid `pattern`: {e}"
+                    ));
+                }
+            }
+            FilterType::And { filters } | FilterType::Or { filters } => {
+                if filters.is_empty() {
+                    problems.push("combined filter has no `filters`".to_string());
+                }
+                problems.extend(filters.iter().flat_map(FilterType::validate));
+            }
+            FilterType::Not { filter } => problems.extend(filter.validate()),
+            FilterType::Unrecognized => {
+                problems.push("`filter_type` has an unknown `type`".to_string());
+            }
+            FilterType::Equals { .. }
+            | FilterType::GreaterThan { .. }
+            | FilterType::LessThan { .. }
+            | FilterType::Between { .. }
+            | FilterType::IsNull { .. }
+            | FilterType::IsNotNull { .. }
+            | FilterType::NoFilter => {}
+        }
+
+        // Nested filters report their own empty columns.
+        if !matches!(
+            self,
+            FilterType::And { .. } | FilterType::Or { .. } | FilterType::Not { .. }
+        ) && self.columns().iter().any(|column| column.trim().is_empty())
+        {
+            problems.push("filter has an empty `column`".to_string());
+        }
+
+        problems
+    }
+}
@@ -0,0 +1,12 @@
+use rustic_transformator::transformator_registry::TransformatorRegistry;
+
+/// Returns a registry with every built-in transformator, the base and the faker ones.
+///
+/// Downstream crates register their own transformators on the returned registry,
+/// before the run starts.
+pub fn builtin_transformator_registry() -> TransformatorRegistry {
+    let mut registry = TransformatorRegistry::new();
+    rustic_base_transformations::register_transformators(&mut registry);
+    rustic_faker_transformations::faker_transformators::register_transformators(&mut registry);
+    registry
+}
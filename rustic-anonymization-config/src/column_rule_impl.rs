@@ -0,0 +1,10 @@
+use crate::config_structs::column_rule_struct::ColumnRule;
+
+impl ColumnRule {
+    /// Checks whether the rule applies to the specified column name.
+    ///
+    /// A rule with an invalid `pattern` never matches; the configuration validation reports it.
+    pub fn matches(&self, column_name: &str) -> bool {
+        self.match_type.matches(&self.pattern, column_name)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::config_structs::column_transformation_struct::AnonymizationColumnTransformation;
+use crate::config_structs::value_policy_struct::ValuePolicy;
+use crate::resolved_anonymization_config::ColumnValuePolicy;
+
+impl AnonymizationColumnTransformation {
+    /// Returns what happens to the `NULL` and empty values of the column, and its cast type.
+    ///
+    /// Both are kept by default. The legacy `retain_if_empty = false` fakes the empty values.
+    pub fn value_policy(&self) -> ColumnValuePolicy {
+        let legacy_on_empty = match self.retain_if_empty {
+            Some(false) => ValuePolicy::Fake,
+            _ => ValuePolicy::Keep,
+        };
+
+        ColumnValuePolicy {
+            column_name: self.column_name.clone(),
+            on_null: self.on_null.unwrap_or_default(),
+            on_empty: self.on_empty.unwrap_or(legacy_on_empty),
+            cast: self.cast,
+        }
+    }
+}
+
+/// Returns the problem of a column setting both the legacy `retain_if_empty` and
+/// `on_null` or `on_empty`, which could contradict each other.
+pub(crate) fn value_policy_problem(
+    retain_if_empty: Option<bool>,
+    on_null: Option<ValuePolicy>,
+    on_empty: Option<ValuePolicy>,
+) -> Option<String> {
+    (retain_if_empty.is_some() && (on_null.is_some() || on_empty.is_some()))
+        .then(|| "`retain_if_empty` cannot be combined with `on_null` or `on_empty`".to_string())
+}
@@ -1,9 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+use super::column_rule_struct::ColumnRule;
+use super::schema_drift_policy_struct::SchemaDriftPolicy;
 use super::table_struct::AnonymizationConfigTable;
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Serialize, Deserialize, Default)]
 pub struct AnonymizationConfig {
+    #[serde(default)]
     pub tables: Vec<AnonymizationConfigTable>,
+    /// Column-name rules applied to every table. The first matching rule wins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_rules: Vec<ColumnRule>,
+    /// The schema drift policy of the tables that do not define their own.
+    #[serde(default, skip_serializing_if = "SchemaDriftPolicy::is_fail")]
+    pub on_schema_drift: SchemaDriftPolicy,
 }
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// The type a transformed column is cast to, instead of keeping the type of the original column.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CastType {
+    String,
+    Boolean,
+    Int32,
+    Int64,
+    Float64,
+    Date,
+    /// A timestamp without a time zone, in microseconds.
+    Datetime,
+    Binary,
+}
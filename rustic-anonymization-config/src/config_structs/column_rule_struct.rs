@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use super::cast_type_struct::CastType;
+use super::name_match_type_struct::NameMatchType;
+use super::transformation_type_struct::AnonymizationTransformationType;
+use super::value_policy_struct::ValuePolicy;
+
+/// A transformation applied to every column whose name matches `pattern`,
+/// in every table, unless the table configures that column explicitly.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ColumnRule {
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "NameMatchType::is_exact")]
+    pub match_type: NameMatchType,
+    pub transformation_type: AnonymizationTransformationType,
+    pub retain_if_empty: Option<bool>,
+    /// The domain the fake values of the matching columns are derived in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// What happens to the `NULL` values of the matching columns. Defaults to `keep`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_null: Option<ValuePolicy>,
+    /// What happens to the empty strings of the matching columns. Defaults to `keep`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_empty: Option<ValuePolicy>,
+    /// The type the matching columns are cast to, once transformed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast: Option<CastType>,
+}
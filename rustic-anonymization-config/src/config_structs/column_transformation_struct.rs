@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use super::transformation_type_struct::AnonymizationTransformationType;
+use super::{
+    cast_type_struct::CastType, filter_type_struct::FilterType, transformation_type_struct::AnonymizationTransformationType,
+    value_policy_struct::ValuePolicy,
+};
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Serialize, Deserialize, Clone)]
@@ -9,4 +12,20 @@ pub struct AnonymizationColumnTransformation {
     pub column_name: String,
     pub transformation_type: AnonymizationTransformationType,
     pub retain_if_empty: Option<bool>,
+    /// The domain the fake values are derived in, such as `person.email`, instead of the column.
+    /// Columns sharing a domain get the same fake value for the same original value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// What happens to the `NULL` values of the column. Defaults to `keep`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_null: Option<ValuePolicy>,
+    /// What happens to the empty strings of the column. Defaults to `keep`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_empty: Option<ValuePolicy>,
+    /// The type the transformed column is cast to. Defaults to the type of the original column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cast: Option<CastType>,
+    /// Only the rows matching this predicate are transformed, the rest keep their original value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<FilterType>,
 }
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoStaticStr};
+
+/// Every built-in transformator that can be used as a `Custom` transformation type.
+///
+/// This is the single source of truth for the `operation_type` names: they are
+/// derived from the variant names, both for serde and for `Into<&'static str>`.
+/// Unknown names are rejected while parsing the configuration.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, EnumIter, IntoStaticStr)]
+#[serde(tag = "operation_type", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CustomOperationType {
+    FakePhoneTransformation,
+    FakeFirstnameTransformation,
+    FakeLastnameTransformation,
+    FakeNameTransformation,
+    FakeEmailTransformation,
+    FakeMultiEmailTransformation,
+    FakeCompanynameTransformation,
+    FakeAddressTransformation,
+    FakeMd5Transformation,
+    FakeEmailWithIdPrefixTransformation {
+        /// The column whose value is used as the prefix of the fake email.
+        #[serde(default = "default_id_column")]
+        id_column: String,
+    },
+}
+
+impl CustomOperationType {
+    /// Returns the `operation_type` name of the transformation, as used in the configuration.
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+}
+
+fn default_id_column() -> String {
+    String::from("id")
+}
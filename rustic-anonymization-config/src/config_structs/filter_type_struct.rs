@@ -1,5 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+use super::filter_value_struct::FilterValue;
+
+/// A row filter of a table.
+///
+/// Every predicate has a `mode`: with `keep` (the default) the matching rows are kept,
+/// with `drop` the matching rows are dropped.
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Serialize, Deserialize, Clone, Default)]
 #[serde(tag = "type")]
@@ -7,33 +13,110 @@ pub enum FilterType {
     Contains {
         column: String,
         value: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
     StartsWith {
         column: String,
         value: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
     EndsWith {
         column: String,
         value: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
     StartsAndEndsWith {
         column: String,
         start_value: String,
         end_value: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
     Equals {
         column: String,
         value: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
-    AnyOfInt {
+    /// Matches the rows where the column is equal to any of the `values`.
+    IsIn {
         column: String,
-        values: Vec<i32>,
+        values: Vec<FilterValue>,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
-    AnyOfString {
+    /// Deprecated alias of `IsIn` with `mode = "drop"`: it drops the matching rows.
+    AnyOfInt { column: String, values: Vec<i32> },
+    /// Deprecated alias of `IsIn` with `mode = "drop"`: it drops the matching rows.
+    AnyOfString { column: String, values: Vec<String> },
+    /// Matches the rows where the column is greater than `value`.
+    GreaterThan {
         column: String,
-        values: Vec<String>,
+        value: FilterValue,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
     },
-    #[serde(other)]
+    /// Matches the rows where the column is less than `value`.
+    LessThan {
+        column: String,
+        value: FilterValue,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
+    },
+    /// Matches the rows where the column is between `start_value` and `end_value`, both inclusive.
+    Between {
+        column: String,
+        start_value: FilterValue,
+        end_value: FilterValue,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
+    },
+    IsNull {
+        column: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
+    },
+    IsNotNull {
+        column: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
+    },
+    /// Matches the rows where the column contains a match of the `pattern` regular expression.
+    Matches {
+        column: String,
+        pattern: String,
+        #[serde(default, skip_serializing_if = "FilterMode::is_keep")]
+        mode: FilterMode,
+    },
+    /// Keeps the rows that match all of the `filters`.
+    And { filters: Vec<FilterType> },
+    /// Keeps the rows that match any of the `filters`.
+    Or { filters: Vec<FilterType> },
+    /// Keeps the rows that do not match `filter`.
+    Not { filter: Box<FilterType> },
     #[default]
     NoFilter,
+    /// Any filter `type` that is not known. Behaves like `NoFilter` at runtime,
+    /// but is reported by the configuration validation.
+    #[serde(other)]
+    Unrecognized,
+}
+
+/// Whether the rows matching a filter predicate are kept or dropped.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterMode {
+    #[default]
+    Keep,
+    Drop,
+}
+
+impl FilterMode {
+    pub(crate) fn is_keep(&self) -> bool {
+        *self == FilterMode::Keep
+    }
 }
@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// A literal value a column is compared with in a filter.
+///
+/// Values are cast to the type of the column when the filter is applied,
+/// so dates and timestamps can be written as strings, e.g. `"2023-01-01"`.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum FilterValue {
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl Display for FilterValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterValue::Boolean(value) => write!(f, "{value}"),
+            FilterValue::Integer(value) => write!(f, "{value}"),
+            FilterValue::Float(value) => write!(f, "{value}"),
+            FilterValue::Text(value) => write!(f, "'{value}'"),
+        }
+    }
+}
@@ -1,6 +1,14 @@
 pub mod anonymization_config;
+pub mod cast_type_struct;
+pub mod column_rule_struct;
 pub mod column_transformation_struct;
+pub mod custom_operation_type_struct;
 pub mod filter_type_struct;
+pub mod filter_value_struct;
+pub mod name_match_type_struct;
+pub mod sampling_type_struct;
+pub mod schema_drift_policy_struct;
 pub mod table_struct;
 pub mod table_type_struct;
 pub mod transformation_type_struct;
+pub mod value_policy_struct;
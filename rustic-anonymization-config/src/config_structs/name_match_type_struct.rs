@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// How a configured name pattern, e.g. a `table_name` or a column rule `pattern`,
+/// is matched against an actual table or column name.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NameMatchType {
+    /// The name must be equal to the pattern.
+    #[default]
+    Exact,
+    /// The pattern is a glob, e.g. `audit_*`.
+    Glob,
+    /// The pattern is a regular expression that must match the whole name.
+    Regex,
+}
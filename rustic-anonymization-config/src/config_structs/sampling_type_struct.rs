@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// How the records of a table are picked when its records are reduced.
+///
+/// The records are picked across all the LOAD files of the table, before any of them is written.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingType {
+    /// The first `keep_num_of_records` rows, in the order of the LOAD files.
+    #[default]
+    Head,
+    /// `keep_num_of_records` rows picked uniformly at random, using the run seed and the table name.
+    Random,
+    /// The `keep_num_of_records` rows with the greatest value in the specified column.
+    LatestBy(String),
+    /// The specified percentage of the rows of the table, picked uniformly at random.
+    Percentage(f64),
+}
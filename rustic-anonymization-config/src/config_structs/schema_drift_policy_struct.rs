@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// What happens when the columns of a Parquet file differ from the columns of its source table.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaDriftPolicy {
+    /// A column missing from the source table fails the table.
+    #[default]
+    Fail,
+    /// Columns missing from the source table are dropped.
+    DropExtraColumns,
+    /// Source columns missing from the Parquet file are added with `NULL` values.
+    /// Columns missing from the source table still fail the table.
+    AddMissingAsNull,
+    /// Parquet columns are renamed to source columns, from the old to the new name.
+    /// Columns still missing from the source table fail the table.
+    Rename(BTreeMap<String, String>),
+}
@@ -1,13 +1,26 @@
 use serde::{Deserialize, Serialize};
 
-use super::{filter_type_struct::FilterType, table_type_struct::AnonymizationConfigTableType};
+use super::{
+    filter_type_struct::FilterType, name_match_type_struct::NameMatchType,
+    sampling_type_struct::SamplingType, schema_drift_policy_struct::SchemaDriftPolicy,
+    table_type_struct::AnonymizationConfigTableType,
+};
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct AnonymizationConfigTable {
+    /// The table name, or a pattern matching several tables, depending on `match_type`.
     pub table_name: String,
+    #[serde(default, skip_serializing_if = "NameMatchType::is_exact")]
+    pub match_type: NameMatchType,
     pub anonymization_type: AnonymizationConfigTableType,
     pub keep_num_of_records: Option<usize>,
+    /// How the kept records are picked, when the records of the table are reduced.
+    #[serde(default, skip_serializing_if = "SamplingType::is_head")]
+    pub sampling: SamplingType,
     pub filter_type: Option<FilterType>,
     pub sanitize_null_bytes: Option<bool>,
+    /// Overrides the global `on_schema_drift` policy for this table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_schema_drift: Option<SchemaDriftPolicy>,
 }
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::column_transformation_struct::AnonymizationColumnTransformation;
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum AnonymizationConfigTableType {
     Multi {
@@ -1,10 +1,22 @@
 use serde::{Deserialize, Serialize};
+use toml::Table;
+
+use super::custom_operation_type_struct::CustomOperationType;
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum AnonymizationTransformationType {
-    Replace { replacement_value: String },
-    Custom { operation_type: String },
+    Replace {
+        replacement_value: String,
+    },
+    Custom(CustomOperationType),
     Nullify,
+    /// A transformator registered by name in the transformator registry,
+    /// either a built-in one or one of a downstream crate.
+    Registered {
+        name: String,
+        #[serde(default, skip_serializing_if = "Table::is_empty")]
+        params: Table,
+    },
 }
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// What happens to the `NULL` or empty values of a transformed column.
+#[cfg_attr(test, derive(Debug))]
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValuePolicy {
+    /// The value is left as is.
+    #[default]
+    Keep,
+    /// The value is transformed like any other value.
+    Fake,
+    /// The value is replaced with `NULL`.
+    Nullify,
+}
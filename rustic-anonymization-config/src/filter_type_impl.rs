@@ -0,0 +1,127 @@
+use std::fmt::Display;
+
+use crate::config_structs::filter_type_struct::{FilterMode, FilterType};
+
+impl FilterType {
+    /// Returns whether the rows matching the filter are kept or dropped.
+    ///
+    /// The deprecated `AnyOfInt` and `AnyOfString` filters always drop the matching rows,
+    /// while `And`, `Or` and `Not` keep the rows matching their combined filters.
+    pub fn mode(&self) -> FilterMode {
+        match self {
+            FilterType::Contains { mode, .. }
+            | FilterType::StartsWith { mode, .. }
+            | FilterType::EndsWith { mode, .. }
+            | FilterType::StartsAndEndsWith { mode, .. }
+            | FilterType::Equals { mode, .. }
+            | FilterType::IsIn { mode, .. }
+            | FilterType::GreaterThan { mode, .. }
+            | FilterType::LessThan { mode, .. }
+            | FilterType::Between { mode, .. }
+            | FilterType::IsNull { mode, .. }
+            | FilterType::IsNotNull { mode, .. }
+            | FilterType::Matches { mode, .. } => *mode,
+            FilterType::AnyOfInt { .. } | FilterType::AnyOfString { .. } => FilterMode::Drop,
+            FilterType::And { .. }
+            | FilterType::Or { .. }
+            | FilterType::Not { .. }
+            | FilterType::NoFilter
+            | FilterType::Unrecognized => FilterMode::Keep,
+        }
+    }
+
+    /// Returns whether the filter, or any of its nested filters, is a deprecated alias.
+    pub fn is_deprecated(&self) -> bool {
+        match self {
+            FilterType::AnyOfInt { .. } | FilterType::AnyOfString { .. } => true,
+            FilterType::And { filters } | FilterType::Or { filters } => {
+                filters.iter().any(FilterType::is_deprecated)
+            }
+            FilterType::Not { filter } => filter.is_deprecated(),
+            _ => false,
+        }
+    }
+
+    /// Writes the condition of the filter, without its mode.
+    fn fmt_condition(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FilterType::Contains { column, value, .. } => {
+                write!(f, "`{column}` contains '{value}'")
+            }
+            FilterType::StartsWith { column, value, .. } => {
+                write!(f, "`{column}` starts with '{value}'")
+            }
+            FilterType::EndsWith { column, value, .. } => {
+                write!(f, "`{column}` ends with '{value}'")
+            }
+            FilterType::StartsAndEndsWith {
+                column,
+                start_value,
+                end_value,
+                ..
+            } => write!(
+                f,
+                "`{column}` starts with '{start_value}' and ends with '{end_value}'"
+            ),
+            FilterType::Equals { column, value, .. } => write!(f, "`{column}` = '{value}'"),
+            FilterType::IsIn { column, values, .. } => {
+                write!(f, "`{column}` in ({})", join(values))
+            }
+            FilterType::AnyOfInt { column, values } => {
+                write!(f, "`{column}` in ({})", join(values))
+            }
+            FilterType::AnyOfString { column, values } => write!(
+                f,
+                "`{column}` in ({})",
+                join(values.iter().map(|value| format!("'{value}'")))
+            ),
+            FilterType::GreaterThan { column, value, .. } => write!(f, "`{column}` > {value}"),
+            FilterType::LessThan { column, value, .. } => write!(f, "`{column}` < {value}"),
+            FilterType::Between {
+                column,
+                start_value,
+                end_value,
+                ..
+            } => write!(f, "`{column}` between {start_value} and {end_value}"),
+            FilterType::IsNull { column, .. } => write!(f, "`{column}` is null"),
+            FilterType::IsNotNull { column, .. } => write!(f, "`{column}` is not null"),
+            FilterType::Matches {
+                column, pattern, ..
+            } => write!(f, "`{column}` matches /{pattern}/"),
+            FilterType::And { filters } => {
+                write!(f, "({})", join_with(filters, " and "))
+            }
+            FilterType::Or { filters } => write!(f, "({})", join_with(filters, " or ")),
+            FilterType::Not { filter } => write!(f, "not ({filter})"),
+            FilterType::NoFilter => write!(f, "any row"),
+            FilterType::Unrecognized => write!(f, "any row (unknown filter type)"),
+        }
+    }
+}
+
+/// Describes the rows a filter keeps, e.g. "`created_at` > '2023-01-01'"
+/// or "not (`country` in ('GR', 'FR'))".
+impl Display for FilterType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mode() {
+            FilterMode::Keep => self.fmt_condition(f),
+            FilterMode::Drop => {
+                write!(f, "not (")?;
+                self.fmt_condition(f)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+fn join<T: Display>(values: impl IntoIterator<Item = T>) -> String {
+    join_with(values, ", ")
+}
+
+fn join_with<T: Display>(values: impl IntoIterator<Item = T>, separator: &str) -> String {
+    values
+        .into_iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
@@ -1,6 +1,17 @@
+pub mod anonymization_config_error;
 pub mod anonymization_config_impl;
 pub mod anonymization_config_table_impl;
+pub mod anonymization_config_validation;
+pub mod builtin_transformators;
+pub mod column_rule_impl;
+pub mod column_transformation_impl;
 pub mod config_structs;
+pub mod filter_type_impl;
+pub mod name_match_type_impl;
+pub mod resolved_anonymization_config;
+pub mod sampling_type_impl;
+pub mod schema_drift_policy_impl;
+pub mod transformation_type_impl;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,76 @@
+use glob::Pattern;
+use regex::Regex;
+
+use crate::config_structs::name_match_type_struct::NameMatchType;
+
+impl NameMatchType {
+    /// Checks whether the specified name matches the pattern.
+    ///
+    /// An invalid pattern never matches; the configuration validation reports it.
+    pub fn matches(&self, pattern: &str, name: &str) -> bool {
+        match self {
+            NameMatchType::Exact => pattern == name,
+            NameMatchType::Glob => Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(name)),
+            NameMatchType::Regex => {
+                Regex::new(&format!("^(?:{pattern})$")).is_ok_and(|regex| regex.is_match(name))
+            }
+        }
+    }
+
+    /// Returns the reason the pattern cannot be compiled, if any.
+    pub(crate) fn pattern_error(&self, pattern: &str) -> Option<String> {
+        match self {
+            NameMatchType::Exact => None,
+            NameMatchType::Glob => Pattern::new(pattern).err().map(|e| e.to_string()),
+            NameMatchType::Regex => Regex::new(pattern).err().map(|e| e.to_string()),
+        }
+    }
+
+    /// Ranks how specific a pattern is, so the most specific of several matching
+    /// patterns can be picked. Higher is more specific.
+    ///
+    /// Exact names rank above globs, and globs above regular expressions.
+    /// Patterns of the same kind are ranked by their number of literal characters.
+    pub(crate) fn specificity(&self, pattern: &str) -> (u8, usize) {
+        match self {
+            NameMatchType::Exact => (2, pattern.len()),
+            NameMatchType::Glob => (
+                1,
+                pattern
+                    .chars()
+                    .filter(|c| !matches!(c, '*' | '?' | '[' | ']' | '!'))
+                    .count(),
+            ),
+            NameMatchType::Regex => (0, regex_literal_chars(pattern)),
+        }
+    }
+
+    pub(crate) fn is_exact(&self) -> bool {
+        *self == NameMatchType::Exact
+    }
+}
+
+/// Counts the characters of a regular expression that are matched literally,
+/// i.e. that are neither metacharacters nor escape sequences.
+fn regex_literal_chars(pattern: &str) -> usize {
+    let mut count = 0;
+    let mut chars = pattern.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                // Escaped metacharacters are literals, character classes like `\d` are not.
+                if chars
+                    .next()
+                    .is_some_and(|escaped| !escaped.is_alphanumeric())
+                {
+                    count += 1;
+                }
+            }
+            '.' | '^' | '$' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' => {}
+            _ => count += 1,
+        }
+    }
+
+    count
+}
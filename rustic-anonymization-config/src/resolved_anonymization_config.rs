@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use rustic_transformator::transformator::Transformator;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
+use tracing::info;
+
+use crate::anonymization_config_validation::ConfigProblem;
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::config_structs::cast_type_struct::CastType;
+use crate::config_structs::filter_type_struct::FilterType;
+use crate::config_structs::schema_drift_policy_struct::SchemaDriftPolicy;
+use crate::config_structs::table_struct::AnonymizationConfigTable;
+use crate::config_structs::value_policy_struct::ValuePolicy;
+
+/// The anonymization configuration of every table of a run, resolved once before the run starts.
+///
+/// Table patterns and column rules are already applied, and the transformators of every
+/// table are already built, so the configuration can be shared, read-only, by every worker.
+pub struct ResolvedAnonymizationConfig {
+    tables: HashMap<String, ResolvedTableConfig>,
+    source_tables: HashMap<String, SourceTableSchema>,
+}
+
+/// The columns of a source table, along with the policy applied when a Parquet file differs.
+pub struct SourceTableSchema {
+    pub column_names: Vec<String>,
+    pub on_schema_drift: SchemaDriftPolicy,
+}
+
+/// The resolved configuration of a single table.
+pub struct ResolvedTableConfig {
+    /// The configuration of the table, with `table_name` set to the actual table name.
+    pub config: AnonymizationConfigTable,
+    pub transformators: Vec<ResolvedTransformator>,
+}
+
+/// A transformator of a table, along with the rows it applies to.
+pub struct ResolvedTransformator {
+    pub transformator: Box<dyn Transformator>,
+    /// Only the rows matching this predicate are transformed, if any.
+    pub when: Option<FilterType>,
+    /// What happens to the `NULL` and empty values of the transformed column, and the type
+    /// it is cast to, `None` for whole table transformators.
+    pub value_policy: Option<ColumnValuePolicy>,
+}
+
+/// What happens to the `NULL` and empty values of the column of a transformator,
+/// and the type its transformed values are cast to.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+#[derive(Clone)]
+pub struct ColumnValuePolicy {
+    pub column_name: String,
+    pub on_null: ValuePolicy,
+    pub on_empty: ValuePolicy,
+    /// `None` keeps the type of the original column.
+    pub cast: Option<CastType>,
+}
+
+impl ResolvedAnonymizationConfig {
+    /// Resolves the configuration of every source table.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The loaded anonymization configuration.
+    /// * `source_tables` - The tables of the run, along with their column names.
+    /// * `registry` - Builds the transformators of the tables.
+    ///
+    /// # Returns
+    ///
+    /// The resolved configuration, or the first transformator that could not be built.
+    /// Tables that are neither configured nor matched by any column rule are left out,
+    /// and are copied as is.
+    pub fn resolve(
+        config: &AnonymizationConfig,
+        source_tables: &HashMap<String, Vec<String>>,
+        registry: &TransformatorRegistry,
+    ) -> Result<Self, ConfigProblem> {
+        let mut tables = HashMap::new();
+        for (table_name, column_names) in source_tables {
+            let Some(table_config) = config.resolve_table_config(table_name, column_names) else {
+                continue;
+            };
+            let transformators = table_config
+                .build_transformators(registry)?
+                .into_iter()
+                .zip(table_config.transformator_conditions())
+                .zip(table_config.transformator_value_policies())
+                .map(
+                    |((transformator, when), value_policy)| ResolvedTransformator {
+                        transformator,
+                        when,
+                        value_policy,
+                    },
+                )
+                .collect::<Vec<_>>();
+
+            info!(
+                "Resolved anonymization config for {table_name}: {} transformator(s)",
+                transformators.len()
+            );
+
+            tables.insert(
+                table_name.clone(),
+                ResolvedTableConfig {
+                    config: table_config,
+                    transformators,
+                },
+            );
+        }
+
+        let source_tables = source_tables
+            .iter()
+            .map(|(table_name, column_names)| {
+                (
+                    table_name.clone(),
+                    SourceTableSchema {
+                        column_names: column_names.clone(),
+                        on_schema_drift: config.schema_drift_policy(table_name).clone(),
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            tables,
+            source_tables,
+        })
+    }
+
+    /// Fetch the resolved configuration of a specific table, if it is anonymized.
+    pub fn table(&self, table_name: &str) -> Option<&ResolvedTableConfig> {
+        self.tables.get(table_name)
+    }
+
+    /// Fetch the source schema of a specific table of the run.
+    pub fn source_table(&self, table_name: &str) -> Option<&SourceTableSchema> {
+        self.source_tables.get(table_name)
+    }
+}
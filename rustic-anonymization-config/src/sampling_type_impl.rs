@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+use crate::config_structs::sampling_type_struct::SamplingType;
+
+impl SamplingType {
+    pub fn is_head(&self) -> bool {
+        matches!(self, SamplingType::Head)
+    }
+
+    /// Returns whether the sampling only applies along with `keep_num_of_records`.
+    pub fn requires_num_of_records(&self) -> bool {
+        !matches!(self, SamplingType::Percentage(_))
+    }
+}
+
+impl Display for SamplingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SamplingType::Head => write!(f, "the first rows"),
+            SamplingType::Random => write!(f, "random rows"),
+            SamplingType::LatestBy(column) => write!(f, "the latest rows by `{column}`"),
+            SamplingType::Percentage(percentage) => write!(f, "{percentage}% of the rows"),
+        }
+    }
+}
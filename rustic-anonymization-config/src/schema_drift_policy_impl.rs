@@ -0,0 +1,28 @@
+use std::fmt::Display;
+
+use crate::config_structs::schema_drift_policy_struct::SchemaDriftPolicy;
+
+impl SchemaDriftPolicy {
+    pub fn is_fail(&self) -> bool {
+        matches!(self, SchemaDriftPolicy::Fail)
+    }
+}
+
+impl Display for SchemaDriftPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaDriftPolicy::Fail => write!(f, "fail"),
+            SchemaDriftPolicy::DropExtraColumns => write!(f, "drop_extra_columns"),
+            SchemaDriftPolicy::AddMissingAsNull => write!(f, "add_missing_as_null"),
+            SchemaDriftPolicy::Rename(mapping) => write!(
+                f,
+                "rename {}",
+                mapping
+                    .iter()
+                    .map(|(from, to)| format!("{from} -> {to}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
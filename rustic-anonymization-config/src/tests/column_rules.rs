@@ -0,0 +1,188 @@
+use crate::config_structs::{
+    anonymization_config::AnonymizationConfig, custom_operation_type_struct::CustomOperationType,
+    table_type_struct::AnonymizationConfigTableType,
+    transformation_type_struct::AnonymizationTransformationType,
+};
+
+use pretty_assertions::assert_eq;
+
+const CONFIG: &str = r#"
+    [[column_rules]]
+    pattern = "email"
+    [column_rules.transformation_type]
+    type = "Custom"
+    operation_type = "fake_email_transformation"
+
+    [[column_rules]]
+    pattern = "*_phone"
+    match_type = "glob"
+    [column_rules.transformation_type]
+    type = "Custom"
+    operation_type = "fake_phone_transformation"
+
+    [[column_rules]]
+    pattern = "(first|last)_name"
+    match_type = "regex"
+    [column_rules.transformation_type]
+    type = "Nullify"
+
+    [[tables]]
+    table_name = "users"
+    [tables.anonymization_type]
+    type = "Multi"
+
+    [[tables.anonymization_type.column_transformations]]
+    column_name = "email"
+    [tables.anonymization_type.column_transformations.transformation_type]
+    type = "Replace"
+    replacement_value = "redacted"
+
+    [[tables]]
+    table_name = "audit_log"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "audit_log_transformation"
+"#;
+
+fn column_names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+/// Returns the column name and transformation type of every column transformation of a table.
+fn resolved_columns(
+    config: &AnonymizationConfig,
+    table_name: &str,
+    columns: &[&str],
+) -> Option<Vec<(String, AnonymizationTransformationType)>> {
+    let table = config.resolve_table_config(table_name, &column_names(columns))?;
+    let AnonymizationConfigTableType::Multi {
+        column_transformations,
+    } = table.anonymization_type
+    else {
+        panic!("Expected Multi type")
+    };
+
+    Some(
+        column_transformations
+            .into_iter()
+            .map(|column_transformation| {
+                (
+                    column_transformation.column_name,
+                    column_transformation.transformation_type,
+                )
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn test_rules_apply_to_tables_without_explicit_config() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    assert_eq!(
+        resolved_columns(
+            &config,
+            "customers",
+            &["id", "email", "mobile_phone", "first_name", "nickname"]
+        ),
+        Some(vec![
+            (
+                "email".to_string(),
+                AnonymizationTransformationType::Custom(
+                    CustomOperationType::FakeEmailTransformation
+                )
+            ),
+            (
+                "mobile_phone".to_string(),
+                AnonymizationTransformationType::Custom(
+                    CustomOperationType::FakePhoneTransformation
+                )
+            ),
+            (
+                "first_name".to_string(),
+                AnonymizationTransformationType::Nullify
+            ),
+        ])
+    );
+}
+
+#[test]
+fn test_explicit_table_config_overrides_rules() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    assert_eq!(
+        resolved_columns(&config, "users", &["id", "email", "last_name"]),
+        Some(vec![
+            (
+                "email".to_string(),
+                AnonymizationTransformationType::Replace {
+                    replacement_value: "redacted".to_string()
+                }
+            ),
+            (
+                "last_name".to_string(),
+                AnonymizationTransformationType::Nullify
+            ),
+        ])
+    );
+}
+
+#[test]
+fn test_rules_do_not_touch_single_tables() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    let table = config
+        .resolve_table_config("audit_log", &column_names(&["email"]))
+        .unwrap();
+    assert_eq!(
+        table.anonymization_type,
+        AnonymizationConfigTableType::Single {
+            transformation: "audit_log_transformation".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_unmatched_table_resolves_to_none() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    assert_eq!(resolved_columns(&config, "orders", &["id", "amount"]), None);
+}
+
+#[test]
+fn test_regex_rules_match_the_whole_column_name() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+    let regex_rule = &config.column_rules[2];
+
+    assert!(regex_rule.matches("first_name"));
+    assert!(!regex_rule.matches("first_name_initial"));
+}
+
+#[test]
+fn test_invalid_rule_patterns_are_reported() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[column_rules]]
+        pattern = "(unclosed"
+        match_type = "regex"
+        [column_rules.transformation_type]
+        type = "Nullify"
+
+        [[column_rules]]
+        pattern = " "
+        [column_rules.transformation_type]
+        type = "Nullify"
+    "#,
+    )
+    .unwrap();
+
+    let problems = config
+        .validate()
+        .iter()
+        .map(|problem| problem.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(problems.len(), 2);
+    assert!(problems[0].starts_with("column rule `(unclosed` has an invalid `pattern`"));
+    assert_eq!(problems[1], "found a column rule with an empty `pattern`");
+}
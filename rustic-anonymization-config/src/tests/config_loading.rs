@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use rustic_config_source::config_root::ConfigRoot;
+
+use crate::anonymization_config_error::AnonymizationConfigError;
+use crate::builtin_transformators::builtin_transformator_registry;
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::resolved_anonymization_config::ResolvedAnonymizationConfig;
+
+use pretty_assertions::assert_eq;
+
+/// Creates an empty configuration directory, unique to the test.
+fn config_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "rustic-anonymization-config-{test_name}-{}",
+        std::process::id()
+    ));
+    _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_missing_config_is_empty() {
+    let config_root = ConfigRoot::new(config_dir("missing"));
+
+    let config = AnonymizationConfig::load_config_for(&config_root, "db", "public").unwrap();
+
+    assert!(config.tables.is_empty());
+    assert!(config.column_rules.is_empty());
+}
+
+#[test]
+fn test_invalid_config_reports_the_file() {
+    let config_root = ConfigRoot::new(config_dir("invalid"));
+    let config_path = config_root.anonymization_config_path("db", "public");
+    std::fs::write(&config_path, "[[tables]]\ntable_name = 42\n").unwrap();
+
+    let error = AnonymizationConfig::load_config_for(&config_root, "db", "public").unwrap_err();
+
+    let AnonymizationConfigError::Parse { path, .. } = &error else {
+        panic!("Expected a parse error, got {error}");
+    };
+    assert_eq!(path, &config_path);
+    assert!(
+        error
+            .to_string()
+            .starts_with(&format!("could not parse {}", config_path.display()))
+    );
+}
+
+#[test]
+fn test_resolve_builds_transformators_per_table() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[column_rules]]
+        pattern = "email"
+        [column_rules.transformation_type]
+        type = "Nullify"
+
+        [[tables]]
+        table_name = "users"
+        keep_num_of_records = 10
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "name"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Nullify"
+    "#,
+    )
+    .unwrap();
+    let source_tables = HashMap::from([
+        (
+            "users".to_string(),
+            vec!["name".to_string(), "email".to_string()],
+        ),
+        ("contacts".to_string(), vec!["email".to_string()]),
+        ("countries".to_string(), vec!["code".to_string()]),
+    ]);
+
+    let resolved = ResolvedAnonymizationConfig::resolve(
+        &config,
+        &source_tables,
+        &builtin_transformator_registry(),
+    )
+    .unwrap();
+
+    let users = resolved.table("users").unwrap();
+    assert_eq!(users.config.keep_num_of_records, Some(10));
+    assert_eq!(users.transformators.len(), 2);
+
+    let contacts = resolved.table("contacts").unwrap();
+    assert_eq!(contacts.config.table_name, "contacts");
+    assert_eq!(contacts.transformators.len(), 1);
+
+    assert!(resolved.table("countries").is_none());
+}
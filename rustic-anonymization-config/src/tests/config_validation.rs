@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+
+use pretty_assertions::assert_eq;
+
+fn problems_of(config: &str) -> Vec<String> {
+    let config: AnonymizationConfig = toml::from_str(config).unwrap();
+    config
+        .validate()
+        .iter()
+        .map(|problem| problem.to_string())
+        .collect()
+}
+
+#[test]
+fn test_valid_config_has_no_problems() {
+    let problems = problems_of(
+        r#"
+        [[tables]]
+        table_name = "table1"
+        [tables.filter_type]
+        type = "Contains"
+        column = "column1"
+        value = "foo"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "column1"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Custom"
+        operation_type = "fake_email_transformation"
+    "#,
+    );
+
+    assert!(problems.is_empty());
+}
+
+#[test]
+fn test_unknown_operation_type_is_rejected() {
+    let config = r#"
+        [[tables]]
+        table_name = "table1"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "column1"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Custom"
+        operation_type = "fake_emial_transformation"
+    "#;
+
+    let error = toml::from_str::<AnonymizationConfig>(config)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(error.contains("unknown variant `fake_emial_transformation`"));
+}
+
+#[test]
+fn test_duplicate_tables_and_columns_are_reported() {
+    let problems = problems_of(
+        r#"
+        [[tables]]
+        table_name = "table1"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "column1"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Nullify"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "column1"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Nullify"
+
+        [[tables]]
+        table_name = "table1"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    );
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `table1`: column `column1` is configured more than once",
+            "table `table1`: is configured more than once",
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_filters_are_reported() {
+    let problems = problems_of(
+        r#"
+        [[tables]]
+        table_name = "table1"
+        [tables.filter_type]
+        type = "AnyOfInt"
+        column = ""
+        values = []
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+
+        [[tables]]
+        table_name = "table2"
+        [tables.filter_type]
+        type = "Contians"
+        column = "column1"
+        value = "foo"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    );
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `table1`: filter on `` has no `values`",
+            "table `table1`: filter has an empty `column`",
+            "table `table2`: `filter_type` has an unknown `type`",
+        ]
+    );
+}
+
+#[test]
+fn test_validate_against_source_reports_missing_tables_and_columns() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[tables]]
+        table_name = "users"
+        [tables.filter_type]
+        type = "Equals"
+        column = "country"
+        value = "GR"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Nullify"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "phone"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Nullify"
+
+        [[tables]]
+        table_name = "old_table"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    )
+    .unwrap();
+
+    let source_tables = HashMap::from([(
+        "users".to_string(),
+        vec![
+            "id".to_string(),
+            "email_address".to_string(),
+            "phone".to_string(),
+        ],
+    )]);
+
+    let problems = config
+        .validate_against_source(&source_tables)
+        .iter()
+        .map(|problem| problem.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `users`: column `email` does not exist in the source table",
+            "table `users`: column `country` does not exist in the source table",
+            "table `old_table`: does not exist in the source schema",
+        ]
+    );
+}
+
+#[test]
+fn test_nested_filter_problems_are_reported() {
+    let problems = problems_of(
+        r#"
+        [[tables]]
+        table_name = "table1"
+        [tables.filter_type]
+        type = "And"
+
+        [[tables.filter_type.filters]]
+        type = "Or"
+        filters = []
+
+        [[tables.filter_type.filters]]
+        type = "Not"
+        [tables.filter_type.filters.filter]
+        type = "Matches"
+        column = "column1"
+        pattern = "(unclosed"
+
+        [[tables.filter_type.filters]]
+        type = "IsNull"
+        column = ""
+
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    );
+
+    assert_eq!(problems.len(), 3);
+    assert_eq!(
+        problems[0],
+        "table `table1`: combined filter has no `filters`"
+    );
+    assert!(
+        problems[1].starts_with("table `table1`: filter on `column1` has an invalid `pattern`")
+    );
+    assert_eq!(problems[2], "table `table1`: filter has an empty `column`");
+}
+
+#[test]
+fn test_invalid_samplings_are_reported() {
+    let problems = problems_of(
+        r#"
+        [[tables]]
+        table_name = "table1"
+        sampling = "random"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+
+        [[tables]]
+        table_name = "table2"
+        keep_num_of_records = 10
+        sampling = { latest_by = "" }
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+
+        [[tables]]
+        table_name = "table3"
+        sampling = { percentage = 150.0 }
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+
+        [[tables]]
+        table_name = "table4"
+        sampling = { percentage = 10.0 }
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    );
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `table1`: `sampling` requires `keep_num_of_records`, unless it is a `percentage`",
+            "table `table2`: `sampling` has an empty `latest_by` column",
+            "table `table3`: `sampling` percentage must be greater than 0 and at most 100",
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_when_predicates_are_reported() {
+    let problems = problems_of(
+        r#"
+        [[tables]]
+        table_name = "users"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Custom"
+        operation_type = "fake_email_transformation"
+        [tables.anonymization_type.column_transformations.when]
+        type = "EndsWith"
+        column = "email"
+        value = ""
+        mode = "drop"
+    "#,
+    );
+
+    assert_eq!(
+        problems,
+        vec!["table `users`: `when` of column `email`: filter on `email` has an empty `value`"]
+    );
+}
+
+#[test]
+fn test_invalid_schema_drift_renames_are_reported() {
+    let problems = problems_of(
+        r#"
+        on_schema_drift = { rename = {} }
+
+        [[tables]]
+        table_name = "users"
+        on_schema_drift = { rename = { fullname = "full_name", name = "full_name" } }
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    );
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `users`: `on_schema_drift` renames more than one column to `full_name`",
+            "`on_schema_drift` rename has no columns",
+        ]
+    );
+}
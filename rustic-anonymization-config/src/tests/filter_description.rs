@@ -0,0 +1,49 @@
+use crate::config_structs::filter_type_struct::FilterType;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_filter_description_states_the_mode() {
+    let filter: FilterType = toml::from_str(
+        r#"
+        type = "And"
+
+        [[filters]]
+        type = "GreaterThan"
+        column = "created_at"
+        value = "2023-01-01"
+
+        [[filters]]
+        type = "IsIn"
+        column = "country"
+        values = ["GR", "FR"]
+        mode = "drop"
+
+        [[filters]]
+        type = "IsNull"
+        column = "deleted_at"
+    "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        filter.to_string(),
+        "(`created_at` > '2023-01-01' and not (`country` in ('GR', 'FR')) and `deleted_at` is null)"
+    );
+    assert!(!filter.is_deprecated());
+}
+
+#[test]
+fn test_any_of_filters_are_deprecated_drop_aliases() {
+    let filter: FilterType = toml::from_str(
+        r#"
+        type = "AnyOfInt"
+        column = "id"
+        values = [1, 2]
+    "#,
+    )
+    .unwrap();
+
+    assert_eq!(filter.to_string(), "not (`id` in (1, 2))");
+    assert!(filter.is_deprecated());
+}
@@ -1,10 +1,22 @@
 use crate::config_structs::{
-    anonymization_config::AnonymizationConfig, filter_type_struct::FilterType,
+    anonymization_config::AnonymizationConfig,
+    custom_operation_type_struct::CustomOperationType,
+    filter_type_struct::{FilterMode, FilterType},
     table_type_struct::AnonymizationConfigTableType,
     transformation_type_struct::AnonymizationTransformationType,
 };
 
 use pretty_assertions::assert_eq;
+use strum::IntoEnumIterator;
+
+mod column_rules;
+mod config_loading;
+mod config_validation;
+mod filter_description;
+mod schema_drift;
+mod table_patterns;
+mod transformator_registry;
+mod value_policies;
 
 #[test]
 fn test_deserialize_config() {
@@ -26,7 +38,8 @@ fn test_deserialize_config() {
         retain_if_empty = true
         [tables.anonymization_type.column_transformations.transformation_type]
         type = "Custom"
-        operation_type = "operation_type"
+        operation_type = "fake_email_with_id_prefix_transformation"
+        id_column = "user_id"
     "#;
 
     let config: AnonymizationConfig = toml::from_str(config).unwrap();
@@ -54,9 +67,11 @@ fn test_deserialize_config() {
         assert_eq!(column_transformations[1].column_name, "column2");
         assert_eq!(
             column_transformations[1].transformation_type,
-            AnonymizationTransformationType::Custom {
-                operation_type: "operation_type".to_string()
-            }
+            AnonymizationTransformationType::Custom(
+                CustomOperationType::FakeEmailWithIdPrefixTransformation {
+                    id_column: "user_id".to_string()
+                }
+            )
         );
         assert!(column_transformations[1].retain_if_empty.unwrap());
     } else {
@@ -123,11 +138,17 @@ fn test_deserialize_config_with_specific_filter_type() {
 
     if let Some(filter) = filter {
         matches!(filter, FilterType::Contains { .. });
-        let FilterType::Contains { column, value } = filter else {
+        let FilterType::Contains {
+            column,
+            value,
+            mode,
+        } = filter
+        else {
             panic!("Expected Contains filter");
         };
         assert_eq!(column, "column1");
         assert_eq!(value, "foo");
+        assert_eq!(*mode, FilterMode::Keep);
     } else {
         panic!("Expected filter");
     }
@@ -145,3 +166,38 @@ fn test_deserialize_config_with_specific_filter_type() {
         panic!("Expected Single type")
     }
 }
+
+#[test]
+fn test_custom_operation_type_names_round_trip() {
+    for operation_type in CustomOperationType::iter() {
+        let config = format!(
+            r#"
+            type = "Custom"
+            operation_type = "{}"
+        "#,
+            operation_type.name()
+        );
+
+        let transformation_type: AnonymizationTransformationType = toml::from_str(&config).unwrap();
+        let AnonymizationTransformationType::Custom(parsed) = &transformation_type else {
+            panic!("Expected Custom type")
+        };
+        assert_eq!(parsed.name(), operation_type.name());
+
+        let serialized = toml::to_string(&transformation_type).unwrap();
+        assert!(serialized.contains(&format!("operation_type = \"{}\"", operation_type.name())));
+    }
+}
+
+#[test]
+fn test_custom_operation_types_are_documented() {
+    let docs = include_str!("../../../docs/ANONYMIZATION_CONFIG.md");
+
+    for operation_type in CustomOperationType::iter() {
+        assert!(
+            docs.contains(&format!("- `{}`", operation_type.name())),
+            "`{}` is missing from docs/ANONYMIZATION_CONFIG.md",
+            operation_type.name()
+        );
+    }
+}
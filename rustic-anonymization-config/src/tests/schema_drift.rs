@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::config_structs::schema_drift_policy_struct::SchemaDriftPolicy;
+
+use pretty_assertions::assert_eq;
+
+const CONFIG: &str = r#"
+    on_schema_drift = "drop_extra_columns"
+
+    [[tables]]
+    table_name = "users"
+    on_schema_drift = { rename = { fullname = "full_name" } }
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "some_transformation"
+
+    [[tables]]
+    table_name = "orders"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "some_transformation"
+"#;
+
+#[test]
+fn test_table_schema_drift_policy_overrides_the_global_one() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    assert_eq!(
+        config.schema_drift_policy("users"),
+        &SchemaDriftPolicy::Rename(BTreeMap::from([(
+            "fullname".to_string(),
+            "full_name".to_string()
+        )]))
+    );
+    assert_eq!(
+        config.schema_drift_policy("orders"),
+        &SchemaDriftPolicy::DropExtraColumns
+    );
+    assert_eq!(
+        config.schema_drift_policy("payments"),
+        &SchemaDriftPolicy::DropExtraColumns
+    );
+}
+
+#[test]
+fn test_schema_drift_policy_fails_by_default() {
+    let config: AnonymizationConfig = toml::from_str("").unwrap();
+
+    assert_eq!(config.schema_drift_policy("users"), &SchemaDriftPolicy::Fail);
+    assert!(
+        !toml::to_string(&config)
+            .unwrap()
+            .contains("on_schema_drift")
+    );
+}
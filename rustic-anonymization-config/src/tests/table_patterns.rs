@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use crate::config_structs::{
+    anonymization_config::AnonymizationConfig, table_type_struct::AnonymizationConfigTableType,
+};
+
+use pretty_assertions::assert_eq;
+
+const CONFIG: &str = r#"
+    [[tables]]
+    table_name = "events_.*"
+    match_type = "regex"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "events_regex"
+
+    [[tables]]
+    table_name = "events_*"
+    match_type = "glob"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "events_glob"
+
+    [[tables]]
+    table_name = "events_2024_*"
+    match_type = "glob"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "events_2024_glob"
+
+    [[tables]]
+    table_name = "events_2024_??"
+    match_type = "glob"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "events_2024_month_glob"
+
+    [[tables]]
+    table_name = "events_2024_01"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "events_2024_01_exact"
+
+    [[tables]]
+    table_name = "audit_[0-9]+"
+    match_type = "regex"
+    [tables.anonymization_type]
+    type = "Single"
+    transformation = "audit_regex"
+"#;
+
+fn matched_transformation(config: &AnonymizationConfig, table_name: &str) -> Option<String> {
+    match &config.fetch_table_config(table_name)?.anonymization_type {
+        AnonymizationConfigTableType::Single { transformation } => Some(transformation.clone()),
+        AnonymizationConfigTableType::Multi { .. } => panic!("Expected Single type"),
+    }
+}
+
+#[test]
+fn test_exact_table_name_wins_over_patterns() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    assert_eq!(
+        matched_transformation(&config, "events_2024_01").as_deref(),
+        Some("events_2024_01_exact")
+    );
+}
+
+#[test]
+fn test_most_specific_pattern_wins() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    // Both `events_2024_*` and `events_2024_??` have 12 literal characters,
+    // so the first declared one wins.
+    assert_eq!(
+        matched_transformation(&config, "events_2024_02").as_deref(),
+        Some("events_2024_glob")
+    );
+    // Globs are more specific than regular expressions.
+    assert_eq!(
+        matched_transformation(&config, "events_2023_12").as_deref(),
+        Some("events_glob")
+    );
+    assert_eq!(
+        matched_transformation(&config, "audit_42").as_deref(),
+        Some("audit_regex")
+    );
+    assert_eq!(matched_transformation(&config, "audit_log"), None);
+}
+
+#[test]
+fn test_resolved_table_config_uses_the_actual_table_name() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    let table = config
+        .resolve_table_config("events_2023_12", &["id".to_string()])
+        .unwrap();
+    assert_eq!(table.table_name, "events_2023_12");
+}
+
+#[test]
+fn test_validate_against_source_resolves_patterns() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[tables]]
+        table_name = "events_*"
+        match_type = "glob"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Nullify"
+
+        [[tables]]
+        table_name = "audit_*"
+        match_type = "glob"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    )
+    .unwrap();
+
+    let source_tables = HashMap::from([
+        (
+            "events_2024_02".to_string(),
+            vec!["id".to_string(), "email".to_string()],
+        ),
+        ("events_2024_01".to_string(), vec!["id".to_string()]),
+    ]);
+
+    let problems = config
+        .validate_against_source(&source_tables)
+        .iter()
+        .map(|problem| problem.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `events_2024_01`: column `email` does not exist in the source table",
+            "table `audit_*`: does not match any table in the source schema",
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_table_pattern_is_reported() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[tables]]
+        table_name = "events_(2024"
+        match_type = "regex"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "some_transformation"
+    "#,
+    )
+    .unwrap();
+
+    let problems = config.validate();
+
+    assert_eq!(problems.len(), 1);
+    assert!(
+        problems[0]
+            .to_string()
+            .starts_with("table `events_(2024`: `table_name` is an invalid pattern")
+    );
+}
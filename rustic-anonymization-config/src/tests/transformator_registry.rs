@@ -0,0 +1,186 @@
+use std::sync::{Arc, Mutex};
+
+use rustic_base_transformations::replace_transformator::ReplaceTransformator;
+use rustic_transformator::transformator_registry::string_param;
+use rustic_transformator::transformator_type::TransformatorType;
+use strum::IntoEnumIterator;
+
+use crate::builtin_transformators::builtin_transformator_registry;
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::config_structs::custom_operation_type_struct::CustomOperationType;
+
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_every_builtin_transformator_is_registered() {
+    let registry = builtin_transformator_registry();
+
+    assert!(registry.contains("replace"));
+    assert!(registry.contains("nullify"));
+    for operation_type in CustomOperationType::iter() {
+        assert!(
+            registry.contains(operation_type.name()),
+            "`{}` is not registered",
+            operation_type.name()
+        );
+    }
+}
+
+#[test]
+fn test_registered_transformators_are_built_with_their_params() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[tables]]
+        table_name = "users"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Registered"
+        name = "masked"
+        params = { mask = "***" }
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "name"
+        retain_if_empty = true
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Registered"
+        name = "fake_name_transformation"
+    "#,
+    )
+    .unwrap();
+    let mut registry = builtin_transformator_registry();
+    registry.register("masked", |column_name, params| {
+        let mask = string_param(params, "mask")?.unwrap_or("*");
+        Ok(Box::new(ReplaceTransformator::new(column_name, mask)))
+    });
+
+    let transformators = config
+        .fetch_table_config("users")
+        .unwrap()
+        .build_transformators(&registry)
+        .unwrap();
+
+    assert_eq!(transformators.len(), 2);
+    assert!(matches!(
+        transformators[0].transformator_type(),
+        TransformatorType::SingleColumn { column_name } if column_name == "email"
+    ));
+    assert!(config.validate_transformators(&registry).is_empty());
+
+    let serialized = toml::to_string(&config).unwrap();
+    assert!(serialized.contains("name = \"masked\""));
+    assert!(!serialized.contains("params = {}"));
+}
+
+#[test]
+fn test_unregistered_transformators_and_invalid_params_are_reported() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[tables]]
+        table_name = "users"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Registered"
+        name = "masked"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "name"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Registered"
+        name = "fake_name_transformation"
+        params = { domain = true }
+
+        [[tables]]
+        table_name = "audit_log"
+        [tables.anonymization_type]
+        type = "Single"
+        transformation = "audit_log_transformation"
+
+        [[column_rules]]
+        pattern = "phone"
+        [column_rules.transformation_type]
+        type = "Registered"
+        name = "fake_mobile_transformation"
+    "#,
+    )
+    .unwrap();
+
+    let problems = config
+        .validate_transformators(&builtin_transformator_registry())
+        .iter()
+        .map(|problem| problem.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `users`: column `email`: invalid configuration: \
+             no transformator is registered as `masked`",
+            "table `users`: column `name`: invalid configuration: \
+             parameter `domain` must be a string",
+            "table `audit_log`: `transformation` `audit_log_transformation`: \
+             invalid configuration: no transformator is registered as `audit_log_transformation`",
+            "column rule `phone`: invalid configuration: \
+             no transformator is registered as `fake_mobile_transformation`",
+        ]
+    );
+}
+
+#[test]
+fn test_domains_of_columns_and_rules_are_passed_to_the_transformators() {
+    let config: AnonymizationConfig = toml::from_str(
+        r#"
+        [[column_rules]]
+        pattern = "customer_email"
+        domain = "person.email"
+        [column_rules.transformation_type]
+        type = "Custom"
+        operation_type = "fake_email_transformation"
+
+        [[tables]]
+        table_name = "users"
+        [tables.anonymization_type]
+        type = "Multi"
+
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        domain = "person.email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Custom"
+        operation_type = "fake_email_transformation"
+    "#,
+    )
+    .unwrap();
+    let domains = Arc::new(Mutex::new(vec![]));
+    let mut registry = builtin_transformator_registry();
+    let seen_domains = domains.clone();
+    registry.register("fake_email_transformation", move |column_name, params| {
+        let domain = string_param(params, "domain")?.unwrap_or_default();
+        seen_domains
+            .lock()
+            .unwrap()
+            .push(format!("{column_name}: {domain}"));
+        Ok(Box::new(ReplaceTransformator::new(column_name, "")))
+    });
+
+    let users = config.fetch_table_config("users").unwrap();
+    let bookings = config
+        .resolve_table_config("bookings", &["customer_email".to_string()])
+        .unwrap();
+    for table_config in [users, &bookings] {
+        table_config.build_transformators(&registry).unwrap();
+    }
+
+    assert_eq!(
+        *domains.lock().unwrap(),
+        vec!["email: person.email", "customer_email: person.email"]
+    );
+    assert!(config.validate_transformators(&registry).is_empty());
+}
@@ -0,0 +1,153 @@
+use crate::builtin_transformators::builtin_transformator_registry;
+use crate::config_structs::anonymization_config::AnonymizationConfig;
+use crate::config_structs::cast_type_struct::CastType;
+use crate::config_structs::value_policy_struct::ValuePolicy;
+use crate::resolved_anonymization_config::ColumnValuePolicy;
+
+use pretty_assertions::assert_eq;
+
+const CONFIG: &str = r#"
+    [[column_rules]]
+    pattern = "phone"
+    on_empty = "nullify"
+    [column_rules.transformation_type]
+    type = "Custom"
+    operation_type = "fake_phone_transformation"
+
+    [[tables]]
+    table_name = "users"
+    [tables.anonymization_type]
+    type = "Multi"
+
+    [[tables.anonymization_type.column_transformations]]
+    column_name = "email"
+    on_null = "fake"
+    [tables.anonymization_type.column_transformations.transformation_type]
+    type = "Custom"
+    operation_type = "fake_email_transformation"
+
+    [[tables.anonymization_type.column_transformations]]
+    column_name = "name"
+    [tables.anonymization_type.column_transformations.transformation_type]
+    type = "Custom"
+    operation_type = "fake_name_transformation"
+
+    [[tables.anonymization_type.column_transformations]]
+    column_name = "company"
+    retain_if_empty = false
+    [tables.anonymization_type.column_transformations.transformation_type]
+    type = "Custom"
+    operation_type = "fake_companyname_transformation"
+"#;
+
+fn value_policy(
+    column_name: &str,
+    on_null: ValuePolicy,
+    on_empty: ValuePolicy,
+) -> ColumnValuePolicy {
+    ColumnValuePolicy {
+        column_name: column_name.to_string(),
+        on_null,
+        on_empty,
+        cast: None,
+    }
+}
+
+#[test]
+fn test_null_and_empty_values_are_kept_by_default() {
+    let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+
+    let users = config
+        .resolve_table_config(
+            "users",
+            &["email", "name", "company", "phone"].map(str::to_string),
+        )
+        .unwrap();
+
+    assert_eq!(
+        users.transformator_value_policies(),
+        vec![
+            Some(value_policy("email", ValuePolicy::Fake, ValuePolicy::Keep)),
+            Some(value_policy("name", ValuePolicy::Keep, ValuePolicy::Keep)),
+            // The legacy `retain_if_empty = false` fakes the empty values.
+            Some(value_policy(
+                "company",
+                ValuePolicy::Keep,
+                ValuePolicy::Fake
+            )),
+            Some(value_policy(
+                "phone",
+                ValuePolicy::Keep,
+                ValuePolicy::Nullify
+            )),
+        ]
+    );
+    assert!(
+        config
+            .validate_transformators(&builtin_transformator_registry())
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_retain_if_empty_cannot_be_combined_with_value_policies() {
+    let config: AnonymizationConfig = toml::from_str(
+        &CONFIG
+            .replace(
+                "on_empty = \"nullify\"",
+                "on_empty = \"nullify\"\nretain_if_empty = true",
+            )
+            .replace(
+                "retain_if_empty = false",
+                "retain_if_empty = false\non_null = \"keep\"",
+            ),
+    )
+    .unwrap();
+
+    let problems = config
+        .validate_transformators(&builtin_transformator_registry())
+        .iter()
+        .map(|problem| problem.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        problems,
+        vec![
+            "table `users`: column `company`: \
+             `retain_if_empty` cannot be combined with `on_null` or `on_empty`",
+            "column rule `phone`: `retain_if_empty` cannot be combined with `on_null` or `on_empty`",
+        ]
+    );
+}
+
+#[test]
+fn test_cast_types_are_resolved_from_columns_and_rules() {
+    let config: AnonymizationConfig = toml::from_str(
+        &CONFIG
+            .replace(
+                "pattern = \"phone\"",
+                "pattern = \"phone\"\ncast = \"int64\"",
+            )
+            .replace(
+                "column_name = \"name\"",
+                "column_name = \"name\"\ncast = \"string\"",
+            ),
+    )
+    .unwrap();
+
+    let users = config
+        .resolve_table_config(
+            "users",
+            &["email", "name", "company", "phone"].map(str::to_string),
+        )
+        .unwrap();
+
+    assert_eq!(
+        users
+            .transformator_value_policies()
+            .into_iter()
+            .map(|value_policy| value_policy.unwrap().cast)
+            .collect::<Vec<_>>(),
+        vec![None, Some(CastType::String), None, Some(CastType::Int64)]
+    );
+}
@@ -0,0 +1,51 @@
+use toml::{Table, Value};
+
+use crate::config_structs::custom_operation_type_struct::CustomOperationType;
+use crate::config_structs::transformation_type_struct::AnonymizationTransformationType;
+
+impl AnonymizationTransformationType {
+    /// Returns the name the transformator is registered under, along with its parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The `domain` of the column, added to the parameters of `Custom` and
+    ///   `Registered` transformators when set.
+    pub fn registry_entry(&self, domain: Option<&str>) -> (&str, Table) {
+        let (name, mut params) = match self {
+            AnonymizationTransformationType::Replace { replacement_value } => {
+                let mut params = Table::new();
+                params.insert(
+                    "replacement_value".to_string(),
+                    Value::String(replacement_value.clone()),
+                );
+                return ("replace", params);
+            }
+            AnonymizationTransformationType::Nullify => return ("nullify", Table::new()),
+            AnonymizationTransformationType::Custom(operation_type) => {
+                (operation_type.name(), operation_type.params())
+            }
+            AnonymizationTransformationType::Registered { name, params } => {
+                (name.as_str(), params.clone())
+            }
+        };
+
+        if let Some(domain) = domain {
+            params
+                .entry("domain")
+                .or_insert(Value::String(domain.to_string()));
+        }
+
+        (name, params)
+    }
+}
+
+impl CustomOperationType {
+    /// Returns the parameters of the operation type, as passed to its registered factory.
+    pub fn params(&self) -> Table {
+        let mut params = Table::new();
+        if let CustomOperationType::FakeEmailWithIdPrefixTransformation { id_column } = self {
+            params.insert("id_column".to_string(), Value::String(id_column.clone()));
+        }
+        params
+    }
+}
@@ -1,87 +1,208 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
-use anyhow::Result;
+use crate::dataframe_transformation::transform_dataframe;
+use crate::record_sampling::{LatestRecords, num_of_random_records, random_records, table_rng};
+use crate::row_subset::RowSubset;
+use crate::schema_drift::{SchemaDriftEvent, reconcile_schema};
+use anyhow::{Result, bail};
 use async_trait::async_trait;
 use aws_sdk_s3::Client as S3Client;
 use aws_sdk_s3::primitives::ByteStream;
 use dms_cdc_operator::dataframe::dataframe_ops::CreateDataframePayload;
 use dms_cdc_operator::dataframe::dataframe_ops::DataframeOperator;
-use polars::prelude::IntoLazy;
-use polars::prelude::{DataFrame, ParquetWriter};
-use polars::prelude::{DataType, NamedFrom, Series, col, lit};
+use polars::prelude::{DataFrame, IdxCa, IdxSize, ParquetWriter};
+use polars::prelude::{DataType, NamedFrom, Series};
 use polars::{
     io::SerReader as _,
     prelude::{ParallelStrategy, ParquetReader},
 };
-use rand::{SeedableRng, rngs::StdRng};
 use rustic_anonymization_config::config_structs::anonymization_config::AnonymizationConfig;
-use rustic_anonymization_config::config_structs::filter_type_struct::FilterType;
+use rustic_anonymization_config::config_structs::sampling_type_struct::SamplingType;
+use rustic_anonymization_config::config_structs::table_struct::AnonymizationConfigTable;
+use rustic_anonymization_config::resolved_anonymization_config::{
+    ResolvedAnonymizationConfig, ResolvedTableConfig,
+};
 use rustic_duration::beautify_duration;
-use rustic_transformator::transformator_type::TransformatorType;
-use rustic_whole_table_transformator::whole_table_transformator::WholeTableTransformator;
+use rustic_run_settings::run_settings::RunSettings;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
 use tracing::error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 pub struct AnonymizationDataFrameOperator<'a> {
     s3_client: &'a S3Client,
+    anonymization_config: Arc<ResolvedAnonymizationConfig>,
+    run_settings: &'a RunSettings,
+    /// The number of records kept so far, per reduced table.
+    kept_num_of_records: Mutex<HashMap<String, usize>>,
+    /// The positions of the records picked from every LOAD file, by file key,
+    /// when a table is sampled across all its LOAD files.
+    sampled_records: Mutex<HashMap<String, Vec<IdxSize>>>,
+    /// The rows to keep, when a subset is extracted.
+    row_subset: Option<Arc<RowSubset>>,
+    /// The schema drifts found so far, in every table.
+    schema_drift_events: Mutex<Vec<SchemaDriftEvent>>,
 }
 
 impl<'a> AnonymizationDataFrameOperator<'a> {
-    pub fn new(s3_client: &'a S3Client) -> Self {
-        Self { s3_client }
+    pub fn new(
+        s3_client: &'a S3Client,
+        anonymization_config: Arc<ResolvedAnonymizationConfig>,
+        run_settings: &'a RunSettings,
+    ) -> Self {
+        Self {
+            s3_client,
+            anonymization_config,
+            run_settings,
+            kept_num_of_records: Mutex::new(HashMap::new()),
+            sampled_records: Mutex::new(HashMap::new()),
+            row_subset: None,
+            schema_drift_events: Mutex::new(vec![]),
+        }
     }
-}
 
-#[async_trait]
-/// Implements the `DataframeOperator` trait for the `AnonymizedDataFrameOperator` struct.
-/// This struct provides methods for creating a dataframe from a Parquet file and applying anonymization transformations.
-impl DataframeOperator for AnonymizationDataFrameOperator<'_> {
-    /// Creates a dataframe from a Parquet file.
+    /// Keeps only the rows of the specified subset of every table.
+    pub fn with_row_subset(mut self, row_subset: Arc<RowSubset>) -> Self {
+        self.row_subset = Some(row_subset);
+        self
+    }
+
+    /// Resolves the anonymization configuration of the tables of a run,
+    /// building their transformators once.
     ///
     /// # Arguments
     ///
-    /// * `payload` - The payload containing information about the Parquet file to be loaded.
+    /// * `anonymization_config` - The loaded anonymization configuration.
+    /// * `source_tables` - The tables of the run, along with their column names.
+    /// * `registry` - The transformators that can be referred to by name.
+    pub fn resolve_config(
+        anonymization_config: &AnonymizationConfig,
+        source_tables: &HashMap<String, Vec<String>>,
+        registry: &TransformatorRegistry,
+    ) -> Result<ResolvedAnonymizationConfig> {
+        let resolved_config =
+            ResolvedAnonymizationConfig::resolve(anonymization_config, source_tables, registry)?;
+        Ok(resolved_config)
+    }
+
+    /// Picks the records of a reduced table across all its LOAD files, before any of them is
+    /// processed: `random` and `percentage` pick among the records of every file, and
+    /// `latest_by` keeps the latest records of the table. The LOAD files are read one
+    /// after the other, so they are downloaded twice.
     ///
-    /// # Returns
+    /// `head` keeps the first records of the LOAD files, in order, so it picks nothing upfront,
+    /// and neither does a sampling keeping every record.
     ///
-    /// Returns a `Result` containing an optional `DataFrame`.
-    /// If the Parquet file is not to be loaded (based on certain conditions), `Ok(None)` is returned.
-    /// Otherwise, `Ok(Some(df))` is returned, where `df` is the loaded dataframe.
-    async fn create_dataframe_from_parquet_file(
+    /// # Arguments
+    ///
+    /// * `table_name` - The table to sample.
+    /// * `load_files` - The LOAD files of the table, in order.
+    pub async fn sample_table_records(
         &self,
-        payload: &CreateDataframePayload,
-    ) -> Result<Option<DataFrame>> {
-        let table_config: AnonymizationConfig = AnonymizationConfig::load_config_for(
-            payload.database_name.as_str(),
-            payload.schema_name.as_str(),
-        );
-        let table_config = table_config.fetch_table_config(&payload.table_name);
-
-        // Check if we are operating on the first load file.
-        // If we do, we need to check if there is a [keep_num_of_records]
-        // option, for this table.
-        // If there is we need to handle it accordingly and skip the
-        // anonymization.
-        //
-        // TODO: Side note (for future us): Check what happens if the number
-        // of rows in the `.parquet` file is not enough based on the
-        // [keep_num_of_records] option.
-        let is_first_load_file = payload.key.contains("LOAD00000001");
-        let has_num_of_records = match table_config {
-            Some(table_config) => table_config.keep_num_of_records.is_some(),
-            None => false,
+        table_name: &str,
+        load_files: &[CreateDataframePayload],
+    ) -> Result<()> {
+        let Some(table_config) = self.reduced_table_config(table_name) else {
+            return Ok(());
+        };
+
+        let records = match &table_config.sampling {
+            SamplingType::Head => return Ok(()),
+            SamplingType::LatestBy(_) if table_config.keep_num_of_records.is_none() => {
+                return Ok(());
+            }
+            SamplingType::LatestBy(column) => {
+                let limit = table_config.keep_num_of_records.unwrap_or_default();
+                let mut latest_records = LatestRecords::new(column, limit);
+                for load_file in load_files {
+                    latest_records.add(&self.sampled_dataframe(load_file).await?)?;
+                }
+                latest_records.into_records()?
+            }
+            SamplingType::Random | SamplingType::Percentage(_) => {
+                let mut num_of_records = vec![];
+                for load_file in load_files {
+                    num_of_records.push(self.sampled_dataframe(load_file).await?.height());
+                }
+                let amount = num_of_random_records(
+                    &table_config.sampling,
+                    num_of_records.iter().sum(),
+                    table_config.keep_num_of_records,
+                );
+                let rng = &mut table_rng(self.run_settings.rng_seed, table_name);
+                random_records(&num_of_records, amount, rng)
+            }
         };
 
-        // Check if we allow record reduction.
-        // Controlled by [RECORD_REDUCTION_ENABLED] env variable.
-        let record_reduction_is_enabled = is_record_reduction_enabled();
+        info!(
+            "{table_name} records sampled across {num_of_files} LOAD file(s): kept {kept}",
+            num_of_files = load_files.len(),
+            kept = records.iter().map(Vec::len).sum::<usize>(),
+        );
+        self.sampled_records.lock().unwrap().extend(
+            load_files
+                .iter()
+                .map(|load_file| load_file.key.clone())
+                .zip(records),
+        );
 
-        // If it is a LOAD file, and it is not the first one, we should not load the file
-        if !is_first_load_file && has_num_of_records && record_reduction_is_enabled {
-            return Ok(None);
+        Ok(())
+    }
+
+    /// Takes the schema drifts found so far, in every table.
+    pub fn take_schema_drift_events(&self) -> Vec<SchemaDriftEvent> {
+        std::mem::take(&mut self.schema_drift_events.lock().unwrap())
+    }
+
+    // The bucket the anonymized Parquet files are uploaded to, if they are uploaded at all.
+    fn anonymized_bucket(&self) -> Option<&str> {
+        self.run_settings
+            .anonymized_bucket
+            .as_deref()
+            .filter(|_| self.run_settings.upload_anonymized_files)
+    }
+
+    // The configuration of a table, if its records are reduced, through the
+    // [keep_num_of_records] and [sampling] options.
+    // Controlled by the [record_reduction_enabled] setting.
+    fn reduced_table_config(&self, table_name: &str) -> Option<&AnonymizationConfigTable> {
+        self.anonymization_config
+            .table(table_name)
+            .map(|resolved| &resolved.config)
+            .filter(|table_config| table_config.reduces_records())
+            .filter(|_| self.run_settings.record_reduction_enabled)
+    }
+
+    // Reads the records of a LOAD file the sampling picks from, the same way as
+    // `create_dataframe_from_parquet_file`. Its schema drift is reported when it is processed.
+    async fn sampled_dataframe(&self, payload: &CreateDataframePayload) -> Result<DataFrame> {
+        let file = self.download_parquet_file(payload).await?;
+        let df = ParquetReader::new(std::io::Cursor::new(file))
+            .read_parallel(ParallelStrategy::Auto)
+            .finish()?;
+
+        let df = match self.anonymization_config.source_table(&payload.table_name) {
+            Some(source_table) => {
+                reconcile_schema(
+                    df,
+                    &source_table.column_names,
+                    &source_table.on_schema_drift,
+                )?
+                .0
+            }
+            None => df,
+        };
+
+        match &self.row_subset {
+            Some(row_subset) => row_subset.retain_rows(&payload.table_name, df),
+            None => Ok(df),
         }
+    }
 
-        // Download the relevant `.parquet` file from S3.
+    // Downloads a Parquet file from S3.
+    async fn download_parquet_file(&self, payload: &CreateDataframePayload) -> Result<Vec<u8>> {
         info!("Reading parquet file from S3: {}", payload.key);
         let df_download_start = Instant::now();
         let mut object = self
@@ -104,228 +225,284 @@ impl DataframeOperator for AnonymizationDataFrameOperator<'_> {
             file_vec.extend_from_slice(&bytes);
         }
 
+        Ok(file_vec)
+    }
+
+    fn kept_num_of_records(&self, table_name: &str) -> usize {
+        self.kept_num_of_records
+            .lock()
+            .unwrap()
+            .get(table_name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn add_kept_num_of_records(&self, table_name: &str, num_of_records: usize) {
+        *self
+            .kept_num_of_records
+            .lock()
+            .unwrap()
+            .entry(table_name.to_string())
+            .or_default() += num_of_records;
+    }
+}
+
+#[async_trait]
+/// Implements the `DataframeOperator` trait for the `AnonymizedDataFrameOperator` struct.
+/// This struct provides methods for creating a dataframe from a Parquet file and applying anonymization transformations.
+impl DataframeOperator for AnonymizationDataFrameOperator<'_> {
+    /// Creates a dataframe from a Parquet file.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload containing information about the Parquet file to be loaded.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an optional `DataFrame`.
+    /// If the Parquet file is not to be loaded (based on certain conditions), `Ok(None)` is returned.
+    /// Otherwise, `Ok(Some(df))` is returned, where `df` is the loaded dataframe.
+    async fn create_dataframe_from_parquet_file(
+        &self,
+        payload: &CreateDataframePayload,
+    ) -> Result<Option<DataFrame>> {
+        let resolved_table_config = self.anonymization_config.table(&payload.table_name);
+        let reduced_table_config = self.reduced_table_config(&payload.table_name);
+
+        // The records of a reduced table are sampled from its LOAD files only,
+        // so CDC files are skipped. The records picked by `sample_table_records`
+        // are taken from every LOAD file, and `head` keeps the first records of
+        // the LOAD files, in order: the [keep_num_of_records] budget is shared
+        // by all of them, so a LOAD file with fewer rows leaves the rest of the
+        // budget to the next ones.
+        let is_load_file = payload.key.contains("LOAD");
+        let sampled_records = self.sampled_records.lock().unwrap().remove(&payload.key);
+        let remaining_num_of_records = match reduced_table_config {
+            Some(_) if !is_load_file => return Ok(None),
+            Some(table_config) if table_config.sampling.is_head() => {
+                table_config.keep_num_of_records.map(|keep_num_of_records| {
+                    keep_num_of_records
+                        .saturating_sub(self.kept_num_of_records(&payload.table_name))
+                })
+            }
+            _ => None,
+        };
+
+        if remaining_num_of_records == Some(0)
+            || sampled_records.as_ref().is_some_and(Vec::is_empty)
+        {
+            info!(
+                "{table} already has all its records, skipping {key}",
+                table = &payload.table_name,
+                key = &payload.key,
+            );
+            return Ok(None);
+        }
+
+        // Download the relevant `.parquet` file from S3.
+        let file_vec = self.download_parquet_file(payload).await?;
+
         // Prepare to load the `.parquet` file.
         let df_load_start = Instant::now();
         let cursor = std::io::Cursor::new(file_vec);
         let reader = ParquetReader::new(cursor);
-        let mut df = reader;
+        let df = reader;
 
-        // If we are operating on the first `LOAD` file,
-        // we check for the [keep_num_of_records] option,
+        // When the first records are kept, only the remaining ones are read,
         // in order to avoid loading the full Dataframe in memory.
-        let df = if record_reduction_is_enabled {
-            if let Some(table_config) = table_config {
-                if is_first_load_file {
-                    let slice_size = table_config
-                        .keep_num_of_records
-                        .unwrap_or(df.num_rows().unwrap());
-                    df.with_slice(Some((0, slice_size)))
-                        .read_parallel(ParallelStrategy::Auto)
-                        .finish()
-                        .unwrap()
-                } else {
-                    df.read_parallel(ParallelStrategy::Auto).finish().unwrap()
+        // The rows of a subset may be anywhere in the file, so it is read whole.
+        let df = match (reduced_table_config, remaining_num_of_records) {
+            (Some(table_config), Some(remaining))
+                if table_config.sampling.is_head() && self.row_subset.is_none() =>
+            {
+                df.with_slice(Some((0, remaining)))
+                    .read_parallel(ParallelStrategy::Auto)
+                    .finish()
+                    .unwrap()
+            }
+            _ => df.read_parallel(ParallelStrategy::Auto).finish().unwrap(),
+        };
+
+        // Reconcile the columns of the file with the source table, before
+        // anything refers to them by name.
+        let df = match self.anonymization_config.source_table(&payload.table_name) {
+            Some(source_table) => {
+                let (df, drift) = reconcile_schema(
+                    df,
+                    &source_table.column_names,
+                    &source_table.on_schema_drift,
+                )?;
+                if let Some(drift) = drift {
+                    let resolved = drift.resolved;
+                    let event = SchemaDriftEvent {
+                        table_name: payload.table_name.clone(),
+                        file_name: payload.key.clone(),
+                        drift,
+                    };
+                    warn!("Schema drift in {event}");
+                    let message = event.to_string();
+                    self.schema_drift_events.lock().unwrap().push(event);
+
+                    if !resolved {
+                        bail!(
+                            "Schema of the Parquet file is not the same as the schema of the table: {message}"
+                        );
+                    }
                 }
-            } else {
-                df.read_parallel(ParallelStrategy::Auto).finish().unwrap()
+                df
             }
-        } else {
-            df.read_parallel(ParallelStrategy::Auto).finish().unwrap()
+            None => df,
         };
 
-        let df_load_duration = beautify_duration(df_load_start.elapsed());
-        info!(
-            "{table} parquet file loaded! Time taken: {df_load_duration}",
-            table = &payload.table_name,
-        );
+        // Keep only the rows of the subset, if one is extracted.
+        let df = match &self.row_subset {
+            Some(row_subset) => {
+                let df = row_subset.retain_rows(&payload.table_name, df)?;
+                info!(
+                    "{table} subset rows: {num_of_rows}",
+                    table = &payload.table_name,
+                    num_of_rows = df.height(),
+                );
+                df
+            }
+            None => df,
+        };
 
-        let df = if table_config
-            .and_then(|c| c.sanitize_null_bytes)
-            .unwrap_or(false)
-        {
-            info!("Sanitizing null bytes for table: {}", &payload.table_name);
-            sanitize_null_bytes(df)?
+        let df = if let Some(table_config) = reduced_table_config {
+            let num_of_records = df.height();
+            let df = match (sampled_records, remaining_num_of_records) {
+                (Some(records), _) => df.take(&IdxCa::from_vec("records".into(), records))?,
+                (None, Some(remaining)) => df.head(Some(remaining)),
+                (None, None) => df,
+            };
+            self.add_kept_num_of_records(&payload.table_name, df.height());
+            info!(
+                "{table} records reduced to {sampling}: kept {kept} of {num_of_records}",
+                table = &payload.table_name,
+                sampling = &table_config.sampling,
+                kept = df.height(),
+            );
+            df
         } else {
             df
         };
 
-        // Filter [DataFrame] based on the filter type,
-        // if any was supplied.
-        let df_filter_start = Instant::now();
-        let df = match table_config {
-            Some(table_config) => {
-                if let Some(filter) = &table_config.filter_type {
-                    match filter {
-                        FilterType::Contains { column, value } => {
-                            let filter_expr = col(column.as_str())
-                                .str()
-                                .contains_literal(lit(value.as_str()));
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::StartsWith { column, value } => {
-                            let filter_expr =
-                                col(column.as_str()).str().starts_with(lit(value.as_str()));
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::EndsWith { column, value } => {
-                            let filter_expr =
-                                col(column.as_str()).str().ends_with(lit(value.as_str()));
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::StartsAndEndsWith {
-                            column,
-                            start_value,
-                            end_value,
-                        } => {
-                            let filter_expr = col(column.as_str())
-                                .str()
-                                .starts_with(lit(start_value.as_str()))
-                                .and(
-                                    col(column.as_str())
-                                        .str()
-                                        .ends_with(lit(end_value.as_str())),
-                                );
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::Equals { column, value } => {
-                            let filter_expr = col(column.as_str()).eq(lit(value.as_str()));
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::AnyOfInt { column, values } => {
-                            use polars::prelude::*;
-
-                            let excluded_df = df![
-                                "excluded" => values.clone()
-                            ]
-                            .unwrap();
-
-                            let excluded_as_series = excluded_df
-                                .column("excluded")
-                                .unwrap()
-                                .as_materialized_series()
-                                .clone();
-                            let filter_expr = col(column.as_str())
-                                .is_in(lit(excluded_as_series), true)
-                                .not();
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::AnyOfString { column, values } => {
-                            use polars::prelude::*;
-
-                            let excluded_df = df![
-                                "excluded" => values.clone()
-                            ]
-                            .unwrap();
-
-                            let excluded_as_series = excluded_df
-                                .column("excluded")
-                                .unwrap()
-                                .as_materialized_series()
-                                .clone();
-                            let filter_expr = col(column.as_str())
-                                .is_in(lit(excluded_as_series), true)
-                                .not();
-                            df.lazy().filter(filter_expr).collect()?
-                        }
-                        FilterType::NoFilter => df,
-                    }
-                } else {
-                    df
-                }
-            }
-            None => df,
-        };
-        let df_filter_duration = beautify_duration(df_filter_start.elapsed());
+        let df_load_duration = beautify_duration(df_load_start.elapsed());
         info!(
-            "{table} parquet file filtered! Time taken: {df_filter_duration}",
+            "{table} parquet file loaded! Time taken: {df_load_duration}",
             table = &payload.table_name,
         );
 
         // If there are no `Transformator`s we can return the already
         // read Dataframe.
-        let transformators = if let Some(table_config) = table_config {
-            table_config.build_transformators(whole_table_transformator())
-        } else {
-            if should_upload_anonymized_files() {
+        let Some(resolved_table_config) = resolved_table_config else {
+            if let Some(anonymized_bucket) = self.anonymized_bucket() {
                 copy_parquet_file_to_anonymized_bucket(
                     self.s3_client,
                     payload.bucket_name.as_str(),
                     payload.key.as_str(),
+                    anonymized_bucket,
                 )
                 .await;
             }
             return Ok(Some(df));
         };
 
-        let mut df = df;
-        let df_get_column_names_start = Instant::now();
-        let column_names = df
-            .get_columns()
-            .iter()
-            .map(|s| s.name().to_string())
-            .collect::<Vec<String>>();
-        let df_get_column_names_duration = beautify_duration(df_get_column_names_start.elapsed());
-        info!("Get column names duration: {df_get_column_names_duration}");
-
-        let df_to_owned_start = Instant::now();
-        let df_to_owned_duration = beautify_duration(df_to_owned_start.elapsed());
-        info!("To owned duration: {df_to_owned_duration}");
-
-        let rng_seed = rng_seed();
-
-        info!("Will anonymize with SEED: {rng_seed}!");
+        let pseudonymizer = &pseudonymizer(self.run_settings);
 
-        let rng = &mut StdRng::seed_from_u64(rng_seed);
+        info!("Will anonymize with {pseudonymizer:?}!");
 
-        // Start anonymizing the Dataframe.
         let anonymization_start = Instant::now();
-        transformators
-            .iter()
-            .filter(|transformator| match &transformator.transformator_type() {
-                // In case we have a `NoOpTransformator` we just skip it from the
-                // next operations.
-                TransformatorType::NoOp => false,
-                // Single column `Transformators` need to be checked against the available
-                // columns on the Dataframe.
-                TransformatorType::SingleColumn { column_name } => {
-                    column_names.contains(column_name)
-                }
-                _ => true,
-            })
-            .for_each(|transformator| {
-                transformator
-                    .transform(&df, rng)
-                    .iter()
-                    .for_each(|transformator_output| {
-                        info!("Transforming column: {}", transformator_output.column_name);
-
-                        let start = Instant::now();
-                        _ = df.apply(transformator_output.column_name.as_str(), |_| {
-                            transformator_output.series.clone()
-                        });
-
-                        info!(
-                            "Column transformed! Time taken: {}",
-                            beautify_duration(start.elapsed())
-                        );
-                    });
-            });
+        let mut df = anonymize_dataframe(
+            df,
+            resolved_table_config,
+            pseudonymizer,
+            &payload.table_name,
+            &payload.key,
+        )?;
         info!(
             "Anonymization done! Time taken: {}",
             beautify_duration(anonymization_start.elapsed())
         );
 
-        if !should_upload_anonymized_files() {
+        let Some(anonymized_bucket) = self.anonymized_bucket() else {
             return Ok(Some(df));
-        }
+        };
 
         // Upload anonymized Parquet files to anonymized S3 bucket.
-        upload_parquet_file(self.s3_client, &mut df, payload.key.as_str()).await;
+        upload_parquet_file(
+            self.s3_client,
+            &mut df,
+            payload.key.as_str(),
+            anonymized_bucket,
+        )
+        .await;
 
         Ok(Some(df))
     }
 }
 
+/// Returns the pseudonymizer of a run: keyed when a pseudonymization key is configured,
+/// seeded otherwise.
+pub fn pseudonymizer(run_settings: &RunSettings) -> Pseudonymizer {
+    match run_settings.pseudonymization_key() {
+        Some(key) => Pseudonymizer::keyed(key.as_bytes()),
+        None => Pseudonymizer::seeded(run_settings.rng_seed),
+    }
+}
+
+/// Anonymizes a Dataframe read from a Parquet file of a table.
+///
+/// The null bytes are sanitized first, if the table asks for it. The filter and the
+/// transformators of the table are then applied, through `transform_dataframe`.
+///
+/// # Arguments
+///
+/// * `df` - The Dataframe read from the Parquet file.
+/// * `resolved_table_config` - The resolved configuration of the table.
+/// * `pseudonymizer` - The pseudonymizer of the run.
+/// * `table_name` - The table of the Dataframe.
+/// * `key` - The Parquet file of the Dataframe, used in errors.
+pub fn anonymize_dataframe(
+    df: DataFrame,
+    resolved_table_config: &ResolvedTableConfig,
+    pseudonymizer: &Pseudonymizer,
+    table_name: &str,
+    key: &str,
+) -> Result<DataFrame> {
+    let table_config = &resolved_table_config.config;
+
+    let df = if table_config.sanitize_null_bytes.unwrap_or(false) {
+        info!("Sanitizing null bytes for table: {table_name}");
+        sanitize_null_bytes(df)?
+    } else {
+        df
+    };
+
+    // Filter [DataFrame] based on the filter type,
+    // if any was supplied.
+    let filter = table_config.filter_type.as_ref();
+    if let Some(filter) = filter {
+        if filter.is_deprecated() {
+            warn!(
+                "{table_name} uses the deprecated `AnyOfInt`/`AnyOfString` filters, which drop the matching rows. Use `IsIn` with an explicit `mode` instead.",
+            );
+        }
+        info!("{table_name} filter: keeping rows where {filter}");
+    }
+
+    // Start anonymizing the Dataframe. The filter and the expression
+    // transformators run as a single lazy query, the others row by row.
+    transform_dataframe(
+        df,
+        filter,
+        &resolved_table_config.transformators,
+        pseudonymizer,
+        table_name,
+        key,
+    )
+}
+
 // Nullify cells in all String columns that contain an embedded null byte (\x00).
 // Stripping \x00 is insufficient when the column holds JSON — the source data is
 // truncated at the null byte, leaving invalid JSON that PostgreSQL rejects.
@@ -370,17 +547,17 @@ async fn copy_parquet_file_to_anonymized_bucket(
     s3_client: &S3Client,
     parquet_s3_bucket: &str,
     parquet_s3_key: &str,
+    destination_bucket: &str,
 ) {
     // Upload anonymized Parquet files to anonymized S3 bucket.
     let parquet_copy_start = Instant::now();
 
     let source_bucket_and_object = format!("{parquet_s3_bucket}/{parquet_s3_key}");
-    let destination_bucket = anonymized_bucket();
 
     _ = s3_client
         .copy_object()
         .copy_source(source_bucket_and_object)
-        .bucket(&destination_bucket)
+        .bucket(destination_bucket)
         .key(parquet_s3_key)
         .send()
         .await;
@@ -394,7 +571,12 @@ async fn copy_parquet_file_to_anonymized_bucket(
 }
 
 // Upload anonymized Parquet files to anonymized S3 bucket.
-async fn upload_parquet_file(s3_client: &S3Client, df: &mut DataFrame, parquet_s3_key: &str) {
+async fn upload_parquet_file(
+    s3_client: &S3Client,
+    df: &mut DataFrame,
+    parquet_s3_key: &str,
+    anonymized_bucket: &str,
+) {
     // Upload anonymized Parquet files to anonymized S3 bucket.
     let df_to_parquet_start = Instant::now();
 
@@ -413,7 +595,7 @@ async fn upload_parquet_file(s3_client: &S3Client, df: &mut DataFrame, parquet_s
 
     _ = s3_client
         .put_object()
-        .bucket(anonymized_bucket())
+        .bucket(anonymized_bucket)
         .key(parquet_s3_key)
         .body(file_stream)
         .send()
@@ -426,50 +608,3 @@ async fn upload_parquet_file(s3_client: &S3Client, df: &mut DataFrame, parquet_s
         df_to_parquet_duration = df_to_parquet_duration,
     );
 }
-
-// Control the allowance of reduced dataset generation
-//
-// Note: might come with specific edge cases, refer to the
-// comments above.
-fn is_record_reduction_enabled() -> bool {
-    std::env::var("RECORD_REDUCTION_ENABLED")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse()
-        .unwrap()
-}
-
-// Control the RNG seed of the anonymization
-fn rng_seed() -> u64 {
-    std::env::var("RNG_SEED")
-        .unwrap_or_else(|_| "42".to_string())
-        .parse()
-        .unwrap()
-}
-
-// Control the upload of anonymized Parquet files to the anonymized
-// bucket
-fn should_upload_anonymized_files() -> bool {
-    std::env::var("UPLOAD_ANONYMIZED_FILES")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse()
-        .unwrap()
-}
-
-// Anonymized Parquet files S3 bucket
-fn anonymized_bucket() -> String {
-    std::env::var("ANONYMIZED_BUCKET").expect("ANONYMIZED_BUCKET env var not set!")
-}
-
-#[cfg(not(feature = "open_source"))]
-fn whole_table_transformator() -> impl WholeTableTransformator {
-    use rustic_bg_whole_table_transformator::BgWholeTableTransformator;
-
-    BgWholeTableTransformator::new()
-}
-
-#[cfg(feature = "open_source")]
-fn whole_table_transformator() -> impl WholeTableTransformator {
-    use rustic_whole_table_transformator::whole_table_transformator::NoOpWholeTableTransformator;
-
-    NoOpWholeTableTransformator::new()
-}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use rustic_anonymization_config::resolved_anonymization_config::ResolvedTableConfig;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+
+use crate::anonymization_dataframe_operator::anonymize_dataframe;
+
+/// Name of the temporary column used to match the anonymized rows with the original ones.
+const ROW_COLUMN: &str = "__rustic_witcher_row";
+
+/// The first rows of a Parquet file, before and after their anonymization.
+pub struct DataFramePreview {
+    /// The columns of the Parquet file, in order.
+    pub column_names: Vec<String>,
+    /// The columns whose values or type are changed by the anonymization.
+    pub changed_columns: Vec<String>,
+    pub rows: Vec<PreviewRow>,
+}
+
+/// A row of a Parquet file, before and after its anonymization.
+pub struct PreviewRow {
+    /// The position of the row in the Parquet file.
+    pub index: usize,
+    /// The original values, in the order of `column_names`.
+    pub before: Vec<AnyValue<'static>>,
+    /// The anonymized values, in the order of `column_names`,
+    /// or `None` when the row is dropped by the filter of the table.
+    pub after: Option<Vec<AnyValue<'static>>>,
+}
+
+impl DataFramePreview {
+    /// Returns whether a column is changed by the anonymization.
+    pub fn is_changed(&self, column_name: &str) -> bool {
+        self.changed_columns
+            .iter()
+            .any(|changed_column| changed_column == column_name)
+    }
+}
+
+/// Anonymizes the first rows of a Dataframe, the same way as a snapshot, and pairs every
+/// anonymized row with its original values.
+///
+/// # Arguments
+///
+/// * `df` - The Dataframe read from the Parquet file.
+/// * `resolved_table_config` - The resolved configuration of the table, if it is anonymized.
+/// * `pseudonymizer` - The pseudonymizer of the run.
+/// * `table_name` - The table of the Dataframe.
+/// * `key` - The Parquet file of the Dataframe, used in errors.
+/// * `num_of_rows` - The number of rows to preview.
+pub fn preview_dataframe(
+    df: &DataFrame,
+    resolved_table_config: Option<&ResolvedTableConfig>,
+    pseudonymizer: &Pseudonymizer,
+    table_name: &str,
+    key: &str,
+    num_of_rows: usize,
+) -> Result<DataFramePreview> {
+    let before = df.head(Some(num_of_rows));
+    let column_names = before
+        .get_column_names()
+        .into_iter()
+        .map(|column_name| column_name.to_string())
+        .collect::<Vec<_>>();
+
+    let indexed = before.with_row_index(ROW_COLUMN.into(), None)?;
+    let after = match resolved_table_config {
+        Some(resolved_table_config) => anonymize_dataframe(
+            indexed,
+            resolved_table_config,
+            pseudonymizer,
+            table_name,
+            key,
+        )?,
+        None => indexed,
+    };
+
+    // The filter may drop some rows, so the anonymized rows are matched by their position.
+    let after_rows = after
+        .column(ROW_COLUMN)
+        .context("the anonymization dropped the row numbers of the preview")?
+        .idx()?
+        .into_no_null_iter()
+        .enumerate()
+        .map(|(after_index, index)| (index as usize, after_index))
+        .collect::<HashMap<_, _>>();
+
+    let rows = (0..before.height())
+        .map(|index| {
+            Ok(PreviewRow {
+                index,
+                before: row_values(&before, &column_names, index)?,
+                after: after_rows
+                    .get(&index)
+                    .map(|after_index| row_values(&after, &column_names, *after_index))
+                    .transpose()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let changed_columns = column_names
+        .iter()
+        .enumerate()
+        .filter(|(position, column_name)| {
+            let dtype_changed = after.column(column_name).map_or(true, |column| {
+                column.dtype() != before[column_name.as_str()].dtype()
+            });
+            dtype_changed
+                || rows.iter().any(|row| {
+                    row.after
+                        .as_ref()
+                        .is_some_and(|after| after[*position] != row.before[*position])
+                })
+        })
+        .map(|(_, column_name)| column_name.clone())
+        .collect();
+
+    Ok(DataFramePreview {
+        column_names,
+        changed_columns,
+        rows,
+    })
+}
+
+// A column missing from the Dataframe, such as one dropped by a transformator, has no value.
+fn row_values(
+    df: &DataFrame,
+    column_names: &[String],
+    index: usize,
+) -> Result<Vec<AnyValue<'static>>> {
+    column_names
+        .iter()
+        .map(|column_name| match df.column(column_name) {
+            Ok(column) => Ok(column.get(index)?.into_static()),
+            Err(_) => Ok(AnyValue::Null),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rustic_anonymization_config::builtin_transformators::builtin_transformator_registry;
+    use rustic_anonymization_config::config_structs::anonymization_config::AnonymizationConfig;
+    use rustic_anonymization_config::resolved_anonymization_config::ResolvedAnonymizationConfig;
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [[tables]]
+        table_name = "witchers"
+        [tables.anonymization_type]
+        type = "Multi"
+        [[tables.anonymization_type.column_transformations]]
+        column_name = "email"
+        [tables.anonymization_type.column_transformations.transformation_type]
+        type = "Replace"
+        replacement_value = "redacted"
+        [tables.filter_type]
+        type = "LessThan"
+        column = "id"
+        value = 3
+    "#;
+
+    fn resolved_config(df: &DataFrame) -> ResolvedAnonymizationConfig {
+        let config: AnonymizationConfig = toml::from_str(CONFIG).unwrap();
+        let source_tables = HashMap::from([(
+            "witchers".to_string(),
+            df.get_column_names()
+                .into_iter()
+                .map(|column_name| column_name.to_string())
+                .collect(),
+        )]);
+
+        ResolvedAnonymizationConfig::resolve(
+            &config,
+            &source_tables,
+            &builtin_transformator_registry(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_preview_pairs_anonymized_rows_with_original_ones() {
+        let df = df!(
+            "id" => &[1i32, 2, 3, 4],
+            "name" => &["geralt", "yennefer", "ciri", "jaskier"],
+            "email" => &["g@kaer.morhen", "y@vengerberg", "c@cintra", "j@oxenfurt"],
+        )
+        .unwrap();
+        let resolved_config = resolved_config(&df);
+
+        let preview = preview_dataframe(
+            &df,
+            resolved_config.table("witchers"),
+            &Pseudonymizer::seeded(42),
+            "witchers",
+            "LOAD00000001.parquet",
+            3,
+        )
+        .unwrap();
+
+        assert_eq!(preview.column_names, ["id", "name", "email"]);
+        assert_eq!(preview.changed_columns, ["email"]);
+        assert_eq!(preview.rows.len(), 3);
+        assert_eq!(
+            preview.rows[1].after.as_ref().unwrap(),
+            &[
+                AnyValue::Int32(2),
+                AnyValue::StringOwned("yennefer".into()),
+                AnyValue::StringOwned("redacted".into()),
+            ]
+        );
+        // The third row is dropped by the filter.
+        assert_eq!(preview.rows[2].index, 2);
+        assert!(preview.rows[2].after.is_none());
+    }
+
+    #[test]
+    fn test_preview_of_a_table_without_configuration_changes_nothing() {
+        let df = df!("id" => &[1i32, 2]).unwrap();
+
+        let preview =
+            preview_dataframe(&df, None, &Pseudonymizer::seeded(42), "witchers", "key", 20)
+                .unwrap();
+
+        assert!(preview.changed_columns.is_empty());
+        assert_eq!(preview.rows.len(), 2);
+        assert!(
+            preview
+                .rows
+                .iter()
+                .all(|row| row.after.as_ref() == Some(&row.before))
+        );
+    }
+}
@@ -0,0 +1,938 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use rayon::prelude::*;
+use rustic_anonymization_config::config_structs::cast_type_struct::CastType;
+use rustic_anonymization_config::config_structs::filter_type_struct::FilterType;
+use rustic_anonymization_config::config_structs::value_policy_struct::ValuePolicy;
+use rustic_anonymization_config::resolved_anonymization_config::{
+    ColumnValuePolicy, ResolvedTransformator,
+};
+use rustic_duration::beautify_duration;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator::Transformator;
+use rustic_transformator::transformator_type::TransformatorType;
+use tracing::info;
+
+use crate::filter_expression::{
+    condition_expr, filter_expr, nullify_matched_values, retain_unmatched_values,
+};
+
+/// Prefix of the temporary columns holding the `when` masks of the row-wise transformators.
+const WHEN_COLUMN_PREFIX: &str = "__rustic_witcher_when_";
+
+/// Prefix of the temporary columns holding the masks of the rows the row-wise transformators
+/// replace with `NULL`, because of the value policy of their column.
+const NULL_COLUMN_PREFIX: &str = "__rustic_witcher_null_";
+
+/// Filters a DataFrame and applies the transformators of its table.
+///
+/// The transformators run in the order of the configuration, so a transformator reading a
+/// column sees the outputs of the previous ones. Consecutive transformators with a
+/// `transform_expr` are compiled into a single lazy query, the first ones along with the
+/// filter, while consecutive row-wise transformators run in parallel and their outputs are
+/// applied in order. Every `when` predicate is evaluated on the original values, before any
+/// column is transformed.
+///
+/// The `NULL` and empty values of a column are kept, transformed or replaced with `NULL`
+/// according to the value policy of its transformator, the same way as the `when` predicate.
+///
+/// Transformed columns keep the type of the original column, unless their configuration casts
+/// them to another type. Transformed values that cannot be cast, such as a fake name in an
+/// `Int64` column, fail the Dataframe.
+///
+/// # Arguments
+///
+/// * `df` - The Dataframe read from the Parquet file.
+/// * `filter` - The filter of the table, if any.
+/// * `transformators` - The resolved transformators of the table.
+/// * `pseudonymizer` - The pseudonymizer of the run, shared by the row-wise transformators.
+/// * `table_name` - The table of the Dataframe, used in errors.
+/// * `key` - The Parquet file of the Dataframe, used in errors.
+pub fn transform_dataframe(
+    df: DataFrame,
+    filter: Option<&FilterType>,
+    transformators: &[ResolvedTransformator],
+    pseudonymizer: &Pseudonymizer,
+    table_name: &str,
+    key: &str,
+) -> Result<DataFrame> {
+    let schema = df.schema().clone();
+
+    let filter_expr = filter
+        .map(|filter| filter_expr(filter, &schema))
+        .transpose()?
+        .flatten();
+
+    let mut stages: Vec<Stage> = vec![];
+    // The masks of the transformators that do not run in the lazy query of the filter,
+    // computed by it on the original values.
+    let mut mask_exprs = vec![];
+    for (index, resolved) in transformators.iter().enumerate() {
+        let column_name = match resolved.transformator.transformator_type() {
+            // In case we have a `NoOpTransformator` we just skip it from the
+            // next operations.
+            TransformatorType::NoOp => continue,
+            // Single column `Transformators` need to be checked against the available
+            // columns on the Dataframe.
+            TransformatorType::SingleColumn { column_name } if !schema.contains(&column_name) => {
+                continue;
+            }
+            TransformatorType::SingleColumn { column_name } => Some(column_name),
+            TransformatorType::MultiColumn => None,
+        };
+
+        let condition = resolved
+            .when
+            .as_ref()
+            .map(|when| condition_expr(when, &schema))
+            .transpose()?
+            .flatten();
+        let (kept, nullified) = resolved
+            .value_policy
+            .as_ref()
+            .filter(|value_policy| schema.contains(&value_policy.column_name))
+            .map(|value_policy| value_policy_exprs(value_policy, &schema))
+            .unwrap_or_default();
+        // The rows matching the `when` predicate, without the kept or nullified values.
+        let transformed_rows = all_of([
+            condition.clone(),
+            kept.map(Expr::not),
+            nullified.clone().map(Expr::not),
+        ]);
+        let nullified_rows = nullified.and_then(|nullified| all_of([condition, Some(nullified)]));
+        let cast = resolved
+            .value_policy
+            .as_ref()
+            .and_then(|value_policy| Some((value_policy.column_name.clone(), value_policy.cast?)))
+            .map(|(column_name, cast)| (column_name, cast_dtype(cast)));
+        let expr = match &column_name {
+            Some(_) => resolved
+                .transformator
+                .transform_expr(&schema)
+                .map_err(|e| transform_error(e, table_name, key))?,
+            None => None,
+        };
+
+        let mut mask_column = |prefix: &str, mask: Option<Expr>| {
+            mask.map(|mask| {
+                let mask_column = format!("{prefix}{index}");
+                mask_exprs.push((mask_column.clone(), mask.alias(mask_column.as_str())));
+                mask_column
+            })
+        };
+
+        match (column_name, expr) {
+            (Some(column_name), Some(expr)) => {
+                // Only the expressions of the first stage run in the lazy query of the
+                // filter, the masks of the next ones are computed upfront.
+                let is_first_stage = matches!(stages.as_slice(), [] | [Stage::Exprs { .. }]);
+                let (transformed_rows, nullified_rows, mask_columns) = if is_first_stage {
+                    (transformed_rows, nullified_rows, vec![])
+                } else {
+                    let when_column = mask_column(WHEN_COLUMN_PREFIX, transformed_rows);
+                    let null_column = mask_column(NULL_COLUMN_PREFIX, nullified_rows);
+                    (
+                        when_column.as_deref().map(col),
+                        null_column.as_deref().map(col),
+                        when_column.into_iter().chain(null_column).collect(),
+                    )
+                };
+
+                let original = col(column_name.as_str());
+                let (dtype, original) = match cast {
+                    Some((_, dtype)) => (dtype.clone(), original.strict_cast(dtype)),
+                    None => (schema.try_get(&column_name)?.clone(), original),
+                };
+                let expr = expr.strict_cast(dtype);
+                let expr = match transformed_rows {
+                    Some(transformed_rows) => when(transformed_rows).then(expr).otherwise(original),
+                    None => expr,
+                };
+                let expr = match nullified_rows {
+                    Some(nullified_rows) => when(nullified_rows).then(lit(Null {})).otherwise(expr),
+                    None => expr,
+                };
+                let expr = expr.alias(column_name);
+
+                match stages.last_mut() {
+                    Some(Stage::Exprs {
+                        exprs,
+                        mask_columns: stage_mask_columns,
+                    }) => {
+                        exprs.push(expr);
+                        stage_mask_columns.extend(mask_columns);
+                    }
+                    _ => stages.push(Stage::Exprs {
+                        exprs: vec![expr],
+                        mask_columns,
+                    }),
+                }
+            }
+            _ => {
+                let row_wise_transformator = (
+                    resolved.transformator.as_ref(),
+                    mask_column(WHEN_COLUMN_PREFIX, transformed_rows),
+                    mask_column(NULL_COLUMN_PREFIX, nullified_rows),
+                    cast,
+                );
+
+                match stages.last_mut() {
+                    Some(Stage::RowWise(row_wise_transformators)) => {
+                        row_wise_transformators.push(row_wise_transformator);
+                    }
+                    _ => stages.push(Stage::RowWise(vec![row_wise_transformator])),
+                }
+            }
+        }
+    }
+
+    let mut stages = stages.into_iter().peekable();
+    let mut exprs = match stages.next_if(|stage| matches!(stage, Stage::Exprs { .. })) {
+        Some(Stage::Exprs { exprs, .. }) => exprs,
+        _ => vec![],
+    };
+    let (mask_columns, mask_exprs): (Vec<_>, Vec<_>) = mask_exprs.into_iter().unzip();
+    exprs.extend(mask_exprs);
+
+    let lazy_start = Instant::now();
+    let mut df = if filter_expr.is_none() && exprs.is_empty() {
+        df
+    } else {
+        let mut lazy_df = df.lazy();
+        if let Some(filter_expr) = filter_expr {
+            lazy_df = lazy_df.filter(filter_expr);
+        }
+        if !exprs.is_empty() {
+            lazy_df = lazy_df.with_columns(exprs);
+        }
+        lazy_df
+            .collect()
+            .with_context(|| format!("could not transform table `{table_name}` in {key}"))?
+    };
+    info!(
+        "{table_name} filtered and transformed lazily! Time taken: {}",
+        beautify_duration(lazy_start.elapsed())
+    );
+
+    // The masks are set aside, so that the transformators do not see them.
+    let mut masks = mask_columns
+        .iter()
+        .map(|mask_column| Ok((mask_column.clone(), df.drop_in_place(mask_column)?)))
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    for stage in stages {
+        match stage {
+            Stage::Exprs {
+                exprs,
+                mask_columns,
+            } => {
+                for mask_column in &mask_columns {
+                    df.with_column(masks.remove(mask_column).expect("mask computed upfront"))?;
+                }
+                df = df.lazy().with_columns(exprs).collect().with_context(|| {
+                    format!("could not transform table `{table_name}` in {key}")
+                })?;
+                for mask_column in &mask_columns {
+                    df.drop_in_place(mask_column)?;
+                }
+            }
+            Stage::RowWise(row_wise_transformators) => {
+                let mut take_mask = |mask_column: Option<String>| {
+                    mask_column
+                        .map(|mask_column| -> Result<BooleanChunked> {
+                            let mask = masks.remove(&mask_column).expect("mask computed upfront");
+                            Ok(mask.bool()?.clone())
+                        })
+                        .transpose()
+                };
+                let row_wise_transformators = row_wise_transformators
+                    .into_iter()
+                    .map(|(transformator, when_column, null_column, cast)| {
+                        Ok((
+                            transformator,
+                            take_mask(when_column)?,
+                            take_mask(null_column)?,
+                            cast,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                apply_row_wise_transformators(
+                    &mut df,
+                    row_wise_transformators,
+                    &schema,
+                    pseudonymizer,
+                    table_name,
+                    key,
+                )?;
+            }
+        }
+    }
+
+    Ok(df)
+}
+
+/// A row-wise transformator, with the masks of the rows it transforms and of the rows it
+/// replaces with `NULL`, and the type its column is cast to, if any.
+type RowWiseTransformator<'a, M> = (
+    &'a dyn Transformator,
+    Option<M>,
+    Option<M>,
+    Option<(String, DataType)>,
+);
+
+/// Consecutive transformators of the configuration that run together.
+enum Stage<'a> {
+    /// Transformators with a `transform_expr`, compiled into a single lazy query, along
+    /// with the mask columns they read.
+    Exprs {
+        exprs: Vec<Expr>,
+        mask_columns: Vec<String>,
+    },
+    /// Row-wise transformators, with the names of their mask columns.
+    RowWise(Vec<RowWiseTransformator<'a, String>>),
+}
+
+// Row-wise transformators run in parallel waves, on the same input. A transformator
+// reading other columns starts a new wave, so it sees the outputs of the previous ones.
+fn apply_row_wise_transformators(
+    df: &mut DataFrame,
+    row_wise_transformators: Vec<RowWiseTransformator<BooleanChunked>>,
+    schema: &Schema,
+    pseudonymizer: &Pseudonymizer,
+    table_name: &str,
+    key: &str,
+) -> Result<()> {
+    let mut waves: Vec<Vec<_>> = vec![];
+    for row_wise_transformator in row_wise_transformators {
+        match waves.last_mut() {
+            Some(wave) if row_wise_transformator.0.input_columns().is_empty() => {
+                wave.push(row_wise_transformator);
+            }
+            _ => waves.push(vec![row_wise_transformator]),
+        }
+    }
+
+    for wave in waves {
+        // The pseudonymizer is never advanced, so the outputs do not depend on the
+        // scheduling of the threads.
+        let transformator_outputs = wave
+            .par_iter()
+            .map(|(transformator, ..)| transformator.transform(df, pseudonymizer))
+            .collect::<Vec<_>>();
+
+        // The outputs are applied in the order of the configuration.
+        for ((_, mask, null_mask, cast), transformator_outputs) in
+            wave.iter().zip(transformator_outputs)
+        {
+            let transformator_outputs =
+                transformator_outputs.map_err(|e| transform_error(e, table_name, key))?;
+            for transformator_output in transformator_outputs {
+                let column_name = transformator_output.column_name.as_str();
+                info!("Transforming column: {column_name}");
+
+                let start = Instant::now();
+                let dtype = match cast {
+                    Some((cast_column, dtype)) if cast_column == column_name => Some(dtype),
+                    _ => schema.get(column_name),
+                };
+                let series = match dtype {
+                    Some(dtype) => transformator_output.series.strict_cast(dtype).with_context(|| {
+                        format!(
+                            "could not cast column `{column_name}` of table `{table_name}` in {key} to {dtype}"
+                        )
+                    })?,
+                    None => transformator_output.series,
+                };
+                let series = match mask {
+                    Some(mask) => retain_unmatched_values(
+                        df.column(column_name)?.as_materialized_series(),
+                        series,
+                        mask,
+                    )?,
+                    None => series,
+                };
+                let series = match null_mask {
+                    Some(null_mask) => nullify_matched_values(series, null_mask)?,
+                    None => series,
+                };
+                _ = df.apply(column_name, |_| series);
+
+                info!(
+                    "Column transformed! Time taken: {}",
+                    beautify_duration(start.elapsed())
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Returns the rows whose value is kept and the ones whose value is replaced with `NULL`,
+// instead of being transformed. A `NULL` value stays `NULL` unless it is faked.
+fn value_policy_exprs(
+    value_policy: &ColumnValuePolicy,
+    schema: &Schema,
+) -> (Option<Expr>, Option<Expr>) {
+    let column = col(value_policy.column_name.as_str());
+    let is_null = column.clone().is_null();
+    // Only strings can be empty.
+    let is_empty = (schema.get(&value_policy.column_name) == Some(&DataType::String))
+        .then(|| column.eq(lit("")).fill_null(lit(false)));
+
+    let kept_null = (value_policy.on_null != ValuePolicy::Fake).then_some(is_null);
+    let (kept_empty, nullified_empty) = match value_policy.on_empty {
+        ValuePolicy::Keep => (is_empty, None),
+        ValuePolicy::Nullify => (None, is_empty),
+        ValuePolicy::Fake => (None, None),
+    };
+
+    (any_of([kept_null, kept_empty]), nullified_empty)
+}
+
+fn cast_dtype(cast: CastType) -> DataType {
+    match cast {
+        CastType::String => DataType::String,
+        CastType::Boolean => DataType::Boolean,
+        CastType::Int32 => DataType::Int32,
+        CastType::Int64 => DataType::Int64,
+        CastType::Float64 => DataType::Float64,
+        CastType::Date => DataType::Date,
+        CastType::Datetime => DataType::Datetime(TimeUnit::Microseconds, None),
+        CastType::Binary => DataType::Binary,
+    }
+}
+
+fn all_of(exprs: impl IntoIterator<Item = Option<Expr>>) -> Option<Expr> {
+    exprs.into_iter().flatten().reduce(Expr::and)
+}
+
+fn any_of(exprs: impl IntoIterator<Item = Option<Expr>>) -> Option<Expr> {
+    exprs.into_iter().flatten().reduce(Expr::or)
+}
+
+// Adds the table and file context to an error of a transformator.
+fn transform_error(error: TransformError, table_name: &str, key: &str) -> anyhow::Error {
+    let context = match error.column_name() {
+        Some(column_name) => {
+            format!("could not transform column `{column_name}` of table `{table_name}` in {key}")
+        }
+        None => format!("could not transform table `{table_name}` in {key}"),
+    };
+
+    anyhow::Error::new(error).context(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+    use rustic_anonymization_config::builtin_transformators::builtin_transformator_registry;
+    use rustic_base_transformations::nulify_transformator::NullifyTransformator;
+    use rustic_base_transformations::replace_transformator::ReplaceTransformator;
+    use rustic_transformator::transformator::{Transformator, string_column};
+    use rustic_transformator::transformator_output::TransformatorOutput;
+
+    use super::*;
+
+    // Upper-cases a column row by row, without an expression.
+    struct UppercaseTransformator;
+
+    impl Transformator for UppercaseTransformator {
+        fn transform(
+            &self,
+            input: &DataFrame,
+            _: &Pseudonymizer,
+        ) -> Result<Vec<TransformatorOutput>, TransformError> {
+            let series = string_column(input, "name")?
+                .iter()
+                .map(|value| value.map(str::to_uppercase))
+                .collect::<Vec<_>>();
+
+            Ok(vec![TransformatorOutput {
+                column_name: "name".to_string(),
+                series: Series::new("name".into(), series),
+            }])
+        }
+
+        fn transformator_type(&self) -> TransformatorType {
+            TransformatorType::MultiColumn
+        }
+    }
+
+    // Copies a column into another one, which it transforms.
+    struct CopyTransformator {
+        from: &'static str,
+        to: &'static str,
+    }
+
+    impl Transformator for CopyTransformator {
+        fn transform(
+            &self,
+            input: &DataFrame,
+            _: &Pseudonymizer,
+        ) -> Result<Vec<TransformatorOutput>, TransformError> {
+            let series = string_column(input, self.from)?.into_series();
+
+            Ok(vec![TransformatorOutput {
+                column_name: self.to.to_string(),
+                series: series.with_name(self.to.into()),
+            }])
+        }
+
+        fn transformator_type(&self) -> TransformatorType {
+            TransformatorType::MultiColumn
+        }
+
+        fn input_columns(&self) -> Vec<String> {
+            vec![self.from.to_string()]
+        }
+    }
+
+    // Draws a random number for every row of a column.
+    struct RandomTransformator(&'static str);
+
+    impl Transformator for RandomTransformator {
+        fn transform(
+            &self,
+            input: &DataFrame,
+            pseudonymizer: &Pseudonymizer,
+        ) -> Result<Vec<TransformatorOutput>, TransformError> {
+            let rng = &mut pseudonymizer.rng();
+            let series = (0..input.height())
+                .map(|_| rng.next_u32().to_string())
+                .collect::<Vec<_>>();
+
+            Ok(vec![TransformatorOutput {
+                column_name: self.0.to_string(),
+                series: Series::new(self.0.into(), series),
+            }])
+        }
+
+        fn transformator_type(&self) -> TransformatorType {
+            TransformatorType::MultiColumn
+        }
+    }
+
+    fn resolved(
+        transformator: impl Transformator + 'static,
+        when: Option<&str>,
+    ) -> ResolvedTransformator {
+        ResolvedTransformator {
+            transformator: Box::new(transformator),
+            when: when.map(|when| toml::from_str(when).unwrap()),
+            value_policy: None,
+        }
+    }
+
+    fn test_df() -> DataFrame {
+        df!(
+            "id" => &[1i32, 2, 3, 4],
+            "name" => &["geralt", "yennefer", "ciri", "jaskier"],
+            "email" => &["g@kaer.morhen", "y@vengerberg", "c@cintra", "j@oxenfurt"],
+            "age" => &[Some(90.0), Some(94.0), None, Some(40.0)],
+        )
+        .unwrap()
+    }
+
+    fn strings(df: &DataFrame, column_name: &str) -> Vec<Option<String>> {
+        df.column(column_name)
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|value| value.map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_and_transformators_are_applied_together() {
+        let filter: FilterType = toml::from_str(
+            r#"
+            type = "LessThan"
+            column = "id"
+            value = 4
+        "#,
+        )
+        .unwrap();
+        let transformators = vec![
+            resolved(ReplaceTransformator::new("email", "redacted"), None),
+            resolved(
+                NullifyTransformator::new("age"),
+                Some(
+                    r#"
+                    type = "GreaterThan"
+                    column = "age"
+                    value = 91
+                "#,
+                ),
+            ),
+            resolved(
+                UppercaseTransformator,
+                Some(
+                    r#"
+                    type = "Equals"
+                    column = "email"
+                    value = "c@cintra"
+                "#,
+                ),
+            ),
+        ];
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let df = transform_dataframe(
+            test_df(),
+            Some(&filter),
+            &transformators,
+            pseudonymizer,
+            "witchers",
+            "LOAD00000001.parquet",
+        )
+        .unwrap();
+
+        assert_eq!(df.get_column_names(), ["id", "name", "email", "age"]);
+        assert_eq!(strings(&df, "email"), vec![Some("redacted".to_string()); 3]);
+        assert_eq!(
+            df.column("age")
+                .unwrap()
+                .f64()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(90.0), None, None]
+        );
+        // The `when` predicate sees the email before it is replaced.
+        assert_eq!(
+            strings(&df, "name"),
+            vec![
+                Some("geralt".to_string()),
+                Some("yennefer".to_string()),
+                Some("CIRI".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_expr_keeps_unmatched_values_of_other_types() {
+        let transformators = vec![resolved(
+            ReplaceTransformator::new("id", "0"),
+            Some(
+                r#"
+                type = "GreaterThan"
+                column = "id"
+                value = 2
+            "#,
+            ),
+        )];
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let df = transform_dataframe(
+            test_df(),
+            None,
+            &transformators,
+            pseudonymizer,
+            "witchers",
+            "key",
+        )
+        .unwrap();
+
+        // The replacement value is cast to the type of the column.
+        assert_eq!(
+            df.column("id")
+                .unwrap()
+                .i32()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            vec![1, 2, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_values_that_cannot_be_cast_back_fail_the_table() {
+        let transformators = vec![resolved(ReplaceTransformator::new("id", "redacted"), None)];
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let error = transform_dataframe(
+            test_df(),
+            None,
+            &transformators,
+            pseudonymizer,
+            "witchers",
+            "key",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "could not transform table `witchers` in key"
+        );
+    }
+
+    #[test]
+    fn test_transformed_columns_keep_their_type_unless_cast() {
+        let cast = |column_name: &str, cast| ColumnValuePolicy {
+            column_name: column_name.to_string(),
+            on_null: ValuePolicy::Keep,
+            on_empty: ValuePolicy::Keep,
+            cast,
+        };
+        let transformators = vec![
+            // Row-wise transformators output strings, which are cast back.
+            resolved(RandomTransformator("id"), None),
+            ResolvedTransformator {
+                value_policy: Some(cast("age", Some(CastType::String))),
+                ..resolved(ReplaceTransformator::new("age", "unknown"), None)
+            },
+            ResolvedTransformator {
+                value_policy: Some(cast("name", Some(CastType::Int64))),
+                ..resolved(
+                    CopyTransformator {
+                        from: "id",
+                        to: "name",
+                    },
+                    None,
+                )
+            },
+        ];
+        let df = df!(
+            "id" => &[1i64, 2],
+            "name" => &["geralt", "yennefer"],
+            "age" => &[90.0, 94.0],
+        )
+        .unwrap();
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let df = transform_dataframe(df, None, &transformators, pseudonymizer, "witchers", "key")
+            .unwrap();
+
+        assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(strings(&df, "age"), vec![Some("unknown".to_string()); 2]);
+        let ids = |column_name| {
+            df.column(column_name)
+                .unwrap()
+                .i64()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("name"), ids("id"));
+    }
+
+    #[test]
+    fn test_row_wise_transformators_do_not_share_the_rng() {
+        let transformators = vec![
+            resolved(RandomTransformator("name"), None),
+            resolved(RandomTransformator("email"), None),
+        ];
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let first = transform_dataframe(
+            test_df(),
+            None,
+            &transformators,
+            pseudonymizer,
+            "witchers",
+            "key",
+        )
+        .unwrap();
+        let second = transform_dataframe(
+            test_df(),
+            None,
+            &transformators,
+            pseudonymizer,
+            "witchers",
+            "key",
+        )
+        .unwrap();
+
+        assert_eq!(strings(&first, "name"), strings(&first, "email"));
+        assert_eq!(strings(&first, "name"), strings(&second, "name"));
+    }
+
+    #[test]
+    fn test_transformators_reading_other_columns_see_previous_outputs() {
+        let transformators = vec![
+            resolved(UppercaseTransformator, None),
+            resolved(
+                CopyTransformator {
+                    from: "name",
+                    to: "email",
+                },
+                None,
+            ),
+        ];
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let df = transform_dataframe(
+            test_df(),
+            None,
+            &transformators,
+            pseudonymizer,
+            "witchers",
+            "key",
+        )
+        .unwrap();
+
+        assert_eq!(
+            strings(&df, "email"),
+            ["GERALT", "YENNEFER", "CIRI", "JASKIER"]
+                .map(|name| Some(name.to_string()))
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_transformators_run_in_the_order_of_the_configuration() {
+        let copy_name = || {
+            resolved(
+                CopyTransformator {
+                    from: "name",
+                    to: "email",
+                },
+                None,
+            )
+        };
+        let replace_name = || resolved(ReplaceTransformator::new("name", "redacted"), None);
+        let transform = |transformators: Vec<ResolvedTransformator>| {
+            transform_dataframe(
+                test_df(),
+                None,
+                &transformators,
+                &Pseudonymizer::seeded(42),
+                "witchers",
+                "key",
+            )
+            .unwrap()
+        };
+
+        let df = transform(vec![copy_name(), replace_name()]);
+        assert_eq!(strings(&df, "email"), strings(&test_df(), "name"));
+        assert_eq!(strings(&df, "name"), vec![Some("redacted".to_string()); 4]);
+
+        let df = transform(vec![replace_name(), copy_name()]);
+        assert_eq!(strings(&df, "email"), vec![Some("redacted".to_string()); 4]);
+    }
+
+    #[test]
+    fn test_when_predicates_of_later_stages_see_the_original_values() {
+        let transformators = vec![
+            resolved(UppercaseTransformator, None),
+            resolved(
+                ReplaceTransformator::new("email", "redacted"),
+                Some(
+                    r#"
+                    type = "Equals"
+                    column = "name"
+                    value = "geralt"
+                "#,
+                ),
+            ),
+        ];
+
+        let df = transform_dataframe(
+            test_df(),
+            None,
+            &transformators,
+            &Pseudonymizer::seeded(42),
+            "witchers",
+            "key",
+        )
+        .unwrap();
+
+        assert_eq!(df.get_column_names(), test_df().get_column_names());
+        assert_eq!(
+            strings(&df, "email"),
+            ["redacted", "y@vengerberg", "c@cintra", "j@oxenfurt"]
+                .map(|email| Some(email.to_string()))
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_value_policies_keep_fake_or_nullify_null_and_empty_values() {
+        let value_policy = |column_name: &str, on_null, on_empty| ColumnValuePolicy {
+            column_name: column_name.to_string(),
+            on_null,
+            on_empty,
+            cast: None,
+        };
+        let transformators = vec![
+            ResolvedTransformator {
+                value_policy: Some(value_policy(
+                    "email",
+                    ValuePolicy::Fake,
+                    ValuePolicy::Nullify,
+                )),
+                ..resolved(ReplaceTransformator::new("email", "redacted"), None)
+            },
+            ResolvedTransformator {
+                value_policy: Some(value_policy("name", ValuePolicy::Keep, ValuePolicy::Keep)),
+                ..resolved(RandomTransformator("name"), None)
+            },
+        ];
+        let df = df!(
+            "email" => &[Some("g@kaer.morhen"), None, Some("")],
+            "name" => &[Some("geralt"), None, Some("")],
+        )
+        .unwrap();
+        let pseudonymizer = &Pseudonymizer::seeded(42);
+
+        let df = transform_dataframe(df, None, &transformators, pseudonymizer, "witchers", "key")
+            .unwrap();
+
+        assert_eq!(
+            strings(&df, "email"),
+            vec![
+                Some("redacted".to_string()),
+                Some("redacted".to_string()),
+                None
+            ]
+        );
+        let names = strings(&df, "name");
+        assert_ne!(names[0], Some("geralt".to_string()));
+        assert_eq!(names[1..], [None, Some(String::new())]);
+    }
+
+    #[test]
+    fn test_every_registered_transformator_fakes_null_values_when_asked() {
+        let registry = builtin_transformator_registry();
+        let mut params = toml::Table::new();
+        params.insert("replacement_value".to_string(), "redacted".into());
+        let df = df!(
+            "id" => &[1i32, 2],
+            "email" => &[Some("g@kaer.morhen"), None],
+        )
+        .unwrap();
+
+        // `nullify` turns every value into `NULL`, whatever the policy.
+        for name in registry.names().filter(|name| *name != "nullify") {
+            let transformators = vec![ResolvedTransformator {
+                transformator: registry.build(name, "email", &params).unwrap(),
+                when: None,
+                value_policy: Some(ColumnValuePolicy {
+                    column_name: "email".to_string(),
+                    on_null: ValuePolicy::Fake,
+                    on_empty: ValuePolicy::Keep,
+                    cast: None,
+                }),
+            }];
+
+            let transformed = transform_dataframe(
+                df.clone(),
+                None,
+                &transformators,
+                &Pseudonymizer::seeded(42),
+                "witchers",
+                "key",
+            )
+            .unwrap();
+
+            assert!(strings(&transformed, "email")[1].is_some(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,539 @@
+use anyhow::{Result, anyhow};
+use polars::prelude::*;
+use rustic_anonymization_config::config_structs::filter_type_struct::{FilterMode, FilterType};
+use rustic_anonymization_config::config_structs::filter_value_struct::FilterValue;
+
+/// Compiles a `FilterType` into a single polars `Expr`.
+///
+/// The `schema` of the filtered DataFrame is used to cast every compared value
+/// to the type of its column, e.g. `"2023-01-01"` to a `Date` or a `Datetime`.
+///
+/// # Returns
+///
+/// `Ok(None)` if the filter keeps every row, the compiled `Expr` otherwise, which is never `NULL`.
+/// An error if a filtered column does not exist or a value cannot be cast to the column type.
+pub fn filter_expr(filter: &FilterType, schema: &Schema) -> Result<Option<Expr>> {
+    match filter {
+        FilterType::NoFilter | FilterType::Unrecognized => Ok(None),
+        filter => build_expr(filter, schema).map(Some),
+    }
+}
+
+/// Compiles the `when` predicate of a column transformation into a polars `Expr`.
+///
+/// # Returns
+///
+/// `Ok(None)` if every row is transformed, the rows to transform otherwise.
+pub fn condition_expr(when: &FilterType, schema: &Schema) -> Result<Option<Expr>> {
+    filter_expr(when, schema)
+}
+
+/// Evaluates the `when` predicate of a column transformation on a DataFrame.
+///
+/// # Returns
+///
+/// `Ok(None)` if every row is transformed, the rows to transform otherwise.
+pub fn condition_mask(when: &FilterType, df: &DataFrame) -> Result<Option<BooleanChunked>> {
+    let Some(expr) = condition_expr(when, df.schema())? else {
+        return Ok(None);
+    };
+
+    let mask = df.clone().lazy().select([expr.alias("when")]).collect()?;
+
+    Ok(Some(mask.column("when")?.bool()?.clone()))
+}
+
+/// Keeps the original values of the rows that are not transformed, according to a `mask`
+/// computed by `condition_mask`.
+///
+/// The original values are cast to the type of the transformed ones, unless the
+/// transformed values are all `NULL`, e.g. the output of a `Nullify` transformation.
+pub fn retain_unmatched_values(
+    original: &Series,
+    transformed: Series,
+    mask: &BooleanChunked,
+) -> Result<Series> {
+    let (original, transformed) = if transformed.dtype() == &DataType::Null {
+        (original.clone(), transformed.cast(original.dtype())?)
+    } else {
+        (original.cast(transformed.dtype())?, transformed)
+    };
+
+    Ok(transformed.zip_with(mask, &original)?)
+}
+
+/// Replaces the values of the rows matching the mask with `NULL`.
+pub fn nullify_matched_values(series: Series, mask: &BooleanChunked) -> Result<Series> {
+    let nulls = Series::full_null(series.name().clone(), series.len(), series.dtype());
+
+    Ok(nulls.zip_with(mask, &series)?)
+}
+
+fn build_expr(filter: &FilterType, schema: &Schema) -> Result<Expr> {
+    let expr = match filter {
+        FilterType::Contains { column, value, .. } => col(column.as_str())
+            .str()
+            .contains_literal(lit(value.as_str())),
+        FilterType::StartsWith { column, value, .. } => {
+            col(column.as_str()).str().starts_with(lit(value.as_str()))
+        }
+        FilterType::EndsWith { column, value, .. } => {
+            col(column.as_str()).str().ends_with(lit(value.as_str()))
+        }
+        FilterType::StartsAndEndsWith {
+            column,
+            start_value,
+            end_value,
+            ..
+        } => col(column.as_str())
+            .str()
+            .starts_with(lit(start_value.as_str()))
+            .and(
+                col(column.as_str())
+                    .str()
+                    .ends_with(lit(end_value.as_str())),
+            ),
+        FilterType::Equals { column, value, .. } => col(column.as_str()).eq(lit(value.as_str())),
+        FilterType::IsIn { column, values, .. } => {
+            let values = values
+                .iter()
+                .map(|value| typed_lit(column, value, schema))
+                .collect::<Result<Vec<_>>>()?;
+            col(column.as_str()).is_in(concat_list(values)?.explode(), true)
+        }
+        FilterType::AnyOfInt { column, values } => {
+            col(column.as_str()).is_in(lit(Series::new("values".into(), values)), true)
+        }
+        FilterType::AnyOfString { column, values } => {
+            col(column.as_str()).is_in(lit(Series::new("values".into(), values)), true)
+        }
+        FilterType::GreaterThan { column, value, .. } => {
+            col(column.as_str()).gt(typed_lit(column, value, schema)?)
+        }
+        FilterType::LessThan { column, value, .. } => {
+            col(column.as_str()).lt(typed_lit(column, value, schema)?)
+        }
+        FilterType::Between {
+            column,
+            start_value,
+            end_value,
+            ..
+        } => col(column.as_str())
+            .gt_eq(typed_lit(column, start_value, schema)?)
+            .and(col(column.as_str()).lt_eq(typed_lit(column, end_value, schema)?)),
+        FilterType::IsNull { column, .. } => col(column.as_str()).is_null(),
+        FilterType::IsNotNull { column, .. } => col(column.as_str()).is_not_null(),
+        FilterType::Matches {
+            column, pattern, ..
+        } => col(column.as_str())
+            .str()
+            .contains(lit(pattern.as_str()), true),
+        FilterType::And { filters } => filters
+            .iter()
+            .map(|filter| build_expr(filter, schema))
+            .reduce(|acc, expr| Ok(acc?.and(expr?)))
+            .unwrap_or(Ok(lit(true)))?,
+        FilterType::Or { filters } => filters
+            .iter()
+            .map(|filter| build_expr(filter, schema))
+            .reduce(|acc, expr| Ok(acc?.or(expr?)))
+            .unwrap_or(Ok(lit(true)))?,
+        FilterType::Not { filter } => build_expr(filter, schema)?.not(),
+        FilterType::NoFilter | FilterType::Unrecognized => lit(true),
+    };
+    // A predicate on a `NULL` value does not match, so it is `false` before being
+    // combined or negated.
+    let expr = expr.fill_null(lit(false));
+
+    match filter.mode() {
+        FilterMode::Keep => Ok(expr),
+        FilterMode::Drop => Ok(expr.not()),
+    }
+}
+
+/// Builds a literal for `value`, cast to the type of `column` in the `schema`.
+fn typed_lit(column: &str, value: &FilterValue, schema: &Schema) -> Result<Expr> {
+    let dtype = schema
+        .get(column)
+        .ok_or_else(|| anyhow!("Filter column `{column}` does not exist"))?;
+
+    let literal = match value {
+        FilterValue::Boolean(value) => lit(*value),
+        FilterValue::Integer(value) => lit(*value),
+        FilterValue::Float(value) => lit(*value),
+        FilterValue::Text(value) => lit(value.as_str()),
+    };
+
+    // Strings are parsed rather than cast into temporal types,
+    // so that the format of dates and timestamps can be inferred.
+    let literal = match (value, dtype) {
+        (FilterValue::Text(_), DataType::Date | DataType::Datetime(_, _)) => literal
+            .str()
+            .strptime(dtype.clone(), StrptimeOptions::default(), lit("raise")),
+        _ => literal.strict_cast(dtype.clone()),
+    };
+
+    // Evaluate eagerly, so that an invalid value is reported before any row is filtered.
+    let value = DataFrame::empty()
+        .lazy()
+        .select([literal.alias("value")])
+        .collect()
+        .map_err(|e| anyhow!("Filter value {value} on `{column}` is not a valid {dtype}: {e}"))?;
+
+    Ok(lit(Scalar::new(
+        dtype.clone(),
+        value.column("value")?.get(0)?.into_static(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filtered_ids(df: &DataFrame, filter: &str) -> Vec<i32> {
+        let filter: FilterType = toml::from_str(filter).unwrap();
+        let expr = filter_expr(&filter, df.schema()).unwrap().unwrap();
+        let filtered = df.clone().lazy().filter(expr).collect().unwrap();
+
+        filtered
+            .column("id")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    fn test_df() -> DataFrame {
+        let created_at = Series::new(
+            "created_at".into(),
+            &["2022-06-30", "2023-01-15", "2023-07-01", "2024-02-29"],
+        )
+        .cast(&DataType::Date)
+        .unwrap();
+        let updated_at = Series::new(
+            "updated_at".into(),
+            &[
+                "2022-12-31 23:59:59",
+                "2023-01-01 00:00:00",
+                "2023-01-01 00:00:01",
+                "2024-01-01 12:00:00",
+            ],
+        );
+
+        DataFrame::new(vec![
+            Series::new("id".into(), &[1, 2, 3, 4]).into(),
+            Series::new("country".into(), &["GR", "FR", "DE", "GR"]).into(),
+            created_at.into(),
+            updated_at.into(),
+            Series::new("amount".into(), &[10.5, 20.0, 30.0, 40.25]).into(),
+            Series::new("deleted_at".into(), &[None, Some("x"), None, None]).into(),
+        ])
+        .unwrap()
+        .lazy()
+        .with_column(col("updated_at").str().to_datetime(
+            Some(TimeUnit::Microseconds),
+            None,
+            StrptimeOptions::default(),
+            lit("raise"),
+        ))
+        .collect()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_combined_filter() {
+        let ids = filtered_ids(
+            &test_df(),
+            r#"
+            type = "And"
+
+            [[filters]]
+            type = "GreaterThan"
+            column = "created_at"
+            value = "2023-01-01"
+
+            [[filters]]
+            type = "Or"
+            [[filters.filters]]
+            type = "Equals"
+            column = "country"
+            value = "GR"
+            [[filters.filters]]
+            type = "Equals"
+            column = "country"
+            value = "FR"
+
+            [[filters]]
+            type = "IsNull"
+            column = "deleted_at"
+        "#,
+        );
+
+        assert_eq!(ids, vec![4]);
+    }
+
+    #[test]
+    fn test_numeric_comparisons() {
+        let df = test_df();
+
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "Between"
+                column = "amount"
+                start_value = 20
+                end_value = 30.0
+            "#
+            ),
+            vec![2, 3]
+        );
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "LessThan"
+                column = "id"
+                value = 3
+            "#
+            ),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_timestamp_comparison() {
+        let ids = filtered_ids(
+            &test_df(),
+            r#"
+            type = "LessThan"
+            column = "updated_at"
+            value = "2023-01-01 00:00:01"
+        "#,
+        );
+
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_not_and_matches() {
+        let ids = filtered_ids(
+            &test_df(),
+            r#"
+            type = "Not"
+            [filter]
+            type = "Matches"
+            column = "country"
+            pattern = "^(GR|DE)$"
+        "#,
+        );
+
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn test_filter_modes() {
+        let df = test_df();
+
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "IsIn"
+                column = "country"
+                values = ["GR", "FR"]
+            "#
+            ),
+            vec![1, 2, 4]
+        );
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "IsIn"
+                column = "id"
+                values = [1, 4]
+                mode = "drop"
+            "#
+            ),
+            vec![2, 3]
+        );
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "IsNull"
+                column = "deleted_at"
+                mode = "drop"
+            "#
+            ),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn test_null_values_do_not_match_in_either_mode() {
+        let df = test_df();
+
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "Equals"
+                column = "deleted_at"
+                value = "x"
+            "#
+            ),
+            vec![2]
+        );
+        // The rows whose `deleted_at` is `NULL` do not match, so they are not dropped.
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "Equals"
+                column = "deleted_at"
+                value = "x"
+                mode = "drop"
+            "#
+            ),
+            vec![1, 3, 4]
+        );
+        assert_eq!(
+            filtered_ids(
+                &df,
+                r#"
+                type = "Not"
+                [filter]
+                type = "Equals"
+                column = "deleted_at"
+                value = "x"
+            "#
+            ),
+            vec![1, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_deprecated_any_of_filters_drop_the_matching_rows() {
+        assert_eq!(
+            filtered_ids(
+                &test_df(),
+                r#"
+                type = "AnyOfString"
+                column = "country"
+                values = ["GR"]
+            "#
+            ),
+            vec![2, 3]
+        );
+    }
+
+    #[test]
+    fn test_missing_column_is_an_error() {
+        let filter: FilterType = toml::from_str(
+            r#"
+            type = "GreaterThan"
+            column = "missing"
+            value = 1
+        "#,
+        )
+        .unwrap();
+
+        assert!(filter_expr(&filter, test_df().schema()).is_err());
+    }
+
+    #[test]
+    fn test_no_filter_keeps_every_row() {
+        assert!(
+            filter_expr(&FilterType::NoFilter, test_df().schema())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_condition_mask_keeps_unmatched_values() {
+        let df = test_df();
+        let when: FilterType = toml::from_str(
+            r#"
+            type = "Equals"
+            column = "country"
+            value = "GR"
+            mode = "drop"
+        "#,
+        )
+        .unwrap();
+
+        let mask = condition_mask(&when, &df).unwrap().unwrap();
+        let transformed = Series::new("country".into(), &["XX", "XX", "XX", "XX"]);
+        let country = retain_unmatched_values(
+            df.column("country").unwrap().as_materialized_series(),
+            transformed,
+            &mask,
+        )
+        .unwrap();
+
+        assert_eq!(
+            country.str().unwrap().into_no_null_iter().collect::<Vec<_>>(),
+            vec!["GR", "XX", "XX", "GR"]
+        );
+    }
+
+    #[test]
+    fn test_condition_mask_transforms_null_values_of_negated_predicates() {
+        let df = test_df();
+        let when: FilterType = toml::from_str(
+            r#"
+            type = "Or"
+            [[filters]]
+            type = "Equals"
+            column = "deleted_at"
+            value = "x"
+            mode = "drop"
+            [[filters]]
+            type = "Not"
+            [filters.filter]
+            type = "StartsWith"
+            column = "deleted_at"
+            value = "x"
+        "#,
+        )
+        .unwrap();
+
+        let mask = condition_mask(&when, &df).unwrap().unwrap();
+
+        assert_eq!(
+            mask.into_no_null_iter().collect::<Vec<_>>(),
+            vec![true, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_condition_mask_with_nullified_values() {
+        let df = test_df();
+        let when: FilterType = toml::from_str(
+            r#"
+            type = "LessThan"
+            column = "id"
+            value = 3
+        "#,
+        )
+        .unwrap();
+
+        let mask = condition_mask(&when, &df).unwrap().unwrap();
+        let transformed = Series::full_null("amount".into(), df.height(), &DataType::Null);
+        let amount = retain_unmatched_values(
+            df.column("amount").unwrap().as_materialized_series(),
+            transformed,
+            &mask,
+        )
+        .unwrap();
+
+        assert_eq!(
+            amount.f64().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![None, None, Some(30.0), Some(40.25)]
+        );
+    }
+}
@@ -1 +1,7 @@
 pub mod anonymization_dataframe_operator;
+pub mod dataframe_preview;
+pub mod dataframe_transformation;
+pub mod filter_expression;
+pub mod record_sampling;
+pub mod row_subset;
+pub mod schema_drift;
@@ -0,0 +1,236 @@
+use anyhow::Result;
+use polars::prelude::*;
+use rand::seq::index;
+use rand::{SeedableRng, rngs::StdRng};
+use rand_seeder::SipHasher;
+use rustic_anonymization_config::config_structs::sampling_type_struct::SamplingType;
+
+/// Name of the temporary column holding the position of a record in its LOAD file.
+const RECORD_COLUMN: &str = "__rustic_witcher_record";
+/// Name of the temporary column holding the position of the LOAD file of a record.
+const FILE_COLUMN: &str = "__rustic_witcher_file";
+
+/// Derives the random number generator of the sampling of a table from the seed of the run
+/// and the name of the table, so that every table is sampled independently, whatever their order.
+pub fn table_rng(rng_seed: u64, table_name: &str) -> StdRng {
+    StdRng::from_rng(&mut SipHasher::from((rng_seed, table_name)).into_rng())
+}
+
+/// Returns the number of records a random sampling keeps, out of all the records of a table.
+///
+/// # Arguments
+///
+/// * `sampling` - How the records are picked, `random` or `percentage`.
+/// * `num_of_records` - The number of records of all the LOAD files of the table.
+/// * `limit` - The maximum number of records to keep, if any.
+pub fn num_of_random_records(
+    sampling: &SamplingType,
+    num_of_records: usize,
+    limit: Option<usize>,
+) -> usize {
+    let amount = match sampling {
+        SamplingType::Percentage(percentage) => {
+            (num_of_records as f64 * percentage / 100.0).round() as usize
+        }
+        _ => num_of_records,
+    };
+
+    amount
+        .min(limit.unwrap_or(num_of_records))
+        .min(num_of_records)
+}
+
+/// Picks records uniformly at random among the records of all the LOAD files of a table.
+///
+/// # Arguments
+///
+/// * `num_of_records` - The number of records of every LOAD file, in order.
+/// * `amount` - The number of records to pick.
+/// * `rng` - The random number generator of the table, see `table_rng`.
+///
+/// # Returns
+///
+/// The positions of the picked records of every LOAD file, in order.
+pub fn random_records(
+    num_of_records: &[usize],
+    amount: usize,
+    rng: &mut StdRng,
+) -> Vec<Vec<IdxSize>> {
+    let total = num_of_records.iter().sum();
+    let mut indices = index::sample(rng, total, amount.min(total)).into_vec();
+    indices.sort_unstable();
+
+    let mut records = vec![vec![]; num_of_records.len()];
+    let (mut file, mut offset) = (0, 0);
+    for index in indices {
+        while index >= offset + num_of_records[file] {
+            offset += num_of_records[file];
+            file += 1;
+        }
+        records[file].push((index - offset) as IdxSize);
+    }
+
+    records
+}
+
+/// The records with the greatest value in a column, over all the LOAD files of a table.
+///
+/// Only the column and the positions of the latest records seen so far are kept in memory,
+/// so the LOAD files can be read one after the other.
+pub struct LatestRecords {
+    column: String,
+    limit: usize,
+    num_of_files: usize,
+    latest: Option<DataFrame>,
+}
+
+impl LatestRecords {
+    pub fn new(column: impl Into<String>, limit: usize) -> Self {
+        Self {
+            column: column.into(),
+            limit,
+            num_of_files: 0,
+            latest: None,
+        }
+    }
+
+    /// Adds the records of the next LOAD file.
+    pub fn add(&mut self, df: &DataFrame) -> Result<()> {
+        let candidates = df
+            .select([self.column.as_str()])?
+            .with_row_index(RECORD_COLUMN.into(), None)?
+            .lazy()
+            .with_column(
+                lit(self.num_of_files as IdxSize)
+                    .cast(IDX_DTYPE)
+                    .alias(FILE_COLUMN),
+            );
+        self.num_of_files += 1;
+
+        let candidates = match self.latest.take() {
+            Some(latest) => concat([latest.lazy(), candidates], UnionArgs::default())?,
+            None => candidates,
+        };
+        // Ties are kept in the order of the files and of their records.
+        self.latest = Some(
+            candidates
+                .sort(
+                    [self.column.as_str()],
+                    SortMultipleOptions::default()
+                        .with_order_descending(true)
+                        .with_nulls_last(true)
+                        .with_maintain_order(true),
+                )
+                .limit(self.limit as IdxSize)
+                .collect()?,
+        );
+
+        Ok(())
+    }
+
+    /// Returns the positions of the latest records of every LOAD file, in the order they were added.
+    pub fn into_records(self) -> Result<Vec<Vec<IdxSize>>> {
+        let mut records = vec![vec![]; self.num_of_files];
+        if let Some(latest) = self.latest {
+            let files = latest.column(FILE_COLUMN)?.idx()?.into_no_null_iter();
+            let rows = latest.column(RECORD_COLUMN)?.idx()?.into_no_null_iter();
+            for (file, row) in files.zip(rows) {
+                records[file as usize].push(row);
+            }
+        }
+        records
+            .iter_mut()
+            .for_each(|records| records.sort_unstable());
+
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_files() -> [DataFrame; 3] {
+        [
+            df!(
+                "id" => &[1, 2, 3, 4],
+                "updated_at" => &[Some(5), Some(9), None, Some(1)],
+            )
+            .unwrap(),
+            df!(
+                "id" => &[5, 6, 7],
+                "updated_at" => &[Some(10), Some(3), Some(8)],
+            )
+            .unwrap(),
+            df!(
+                "id" => &[8, 9, 10],
+                "updated_at" => &[Some(2), Some(7), Some(4)],
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_random_records_are_picked_across_every_file() {
+        let random_records = |seed| random_records(&[4, 3, 3], 6, &mut table_rng(seed, "users"));
+
+        let records = random_records(42);
+
+        assert_eq!(records.iter().map(Vec::len).sum::<usize>(), 6);
+        assert!(records.iter().filter(|records| !records.is_empty()).count() > 1);
+        assert!(records.iter().all(|records| records.is_sorted()));
+        assert!(records[0].iter().all(|record| *record < 4));
+        assert!(records[1..].iter().flatten().all(|record| *record < 3));
+        assert_eq!(records, random_records(42));
+    }
+
+    #[test]
+    fn test_random_records_keep_every_record_when_fewer_than_the_amount() {
+        let records = random_records(&[2, 0, 1], 10, &mut table_rng(42, "users"));
+
+        assert_eq!(records, vec![vec![0, 1], vec![], vec![0]]);
+    }
+
+    #[test]
+    fn test_every_table_is_sampled_with_its_own_rng() {
+        let random_records =
+            |table_name| random_records(&[50, 50], 10, &mut table_rng(42, table_name));
+
+        assert_eq!(random_records("users"), random_records("users"));
+        assert_ne!(random_records("users"), random_records("bookings"));
+    }
+
+    #[test]
+    fn test_num_of_random_records_is_capped_by_the_limit() {
+        let percentage = SamplingType::Percentage(50.0);
+
+        assert_eq!(num_of_random_records(&percentage, 10, None), 5);
+        assert_eq!(num_of_random_records(&percentage, 10, Some(2)), 2);
+        assert_eq!(num_of_random_records(&SamplingType::Random, 10, Some(4)), 4);
+        assert_eq!(
+            num_of_random_records(&SamplingType::Random, 10, Some(100)),
+            10
+        );
+    }
+
+    #[test]
+    fn test_latest_records_are_the_latest_of_every_file() {
+        let mut latest_records = LatestRecords::new("updated_at", 4);
+        for df in load_files() {
+            latest_records.add(&df).unwrap();
+        }
+
+        // 10 and 8 of the second file, 9 of the first one and 7 of the third one.
+        assert_eq!(
+            latest_records.into_records().unwrap(),
+            vec![vec![1], vec![0, 2], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_latest_records_by_an_unknown_column_fail() {
+        let mut latest_records = LatestRecords::new("missing", 3);
+
+        assert!(latest_records.add(&load_files()[0]).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use polars::prelude::*;
+
+/// Formats of the `::text` representation of the timestamps of the source database.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+const TIMESTAMPTZ_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f%#z";
+
+/// The rows of a subset extraction, per table.
+///
+/// Rows are identified by the text representation of their key columns, as returned by
+/// the source database. The text is parsed to the type of the key columns of the
+/// DataFrames before comparing, so that e.g. timestamp and numeric keys match whatever
+/// their formatting.
+#[derive(Default)]
+pub struct RowSubset {
+    tables: HashMap<String, SubsetRows>,
+}
+
+struct SubsetRows {
+    key_columns: Vec<String>,
+    keys: HashSet<Vec<String>>,
+}
+
+impl RowSubset {
+    /// Adds a row to the subset.
+    ///
+    /// # Arguments
+    ///
+    /// * `table_name` - The table of the row.
+    /// * `key_columns` - The columns identifying the rows of the table, e.g. its primary key.
+    /// * `key` - The text of the key columns of the row.
+    ///
+    /// # Returns
+    ///
+    /// Whether the row was not already part of the subset.
+    pub fn insert(&mut self, table_name: &str, key_columns: &[String], key: Vec<String>) -> bool {
+        self.tables
+            .entry(table_name.to_string())
+            .or_insert_with(|| SubsetRows {
+                key_columns: key_columns.to_vec(),
+                keys: HashSet::new(),
+            })
+            .keys
+            .insert(key)
+    }
+
+    pub fn contains_table(&self, table_name: &str) -> bool {
+        self.tables.contains_key(table_name)
+    }
+
+    /// Returns the number of rows of each table of the subset.
+    pub fn num_of_rows(&self) -> Vec<(&str, usize)> {
+        let mut num_of_rows = self
+            .tables
+            .iter()
+            .map(|(table_name, rows)| (table_name.as_str(), rows.keys.len()))
+            .collect::<Vec<_>>();
+        num_of_rows.sort_unstable();
+        num_of_rows
+    }
+
+    /// Keeps the rows of the DataFrame of a table that are part of the subset.
+    pub fn retain_rows(&self, table_name: &str, df: DataFrame) -> Result<DataFrame> {
+        let Some(rows) = self.tables.get(table_name) else {
+            return Ok(df.clear());
+        };
+
+        let keys = DataFrame::new(
+            rows.key_columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    Column::new(
+                        column.as_str().into(),
+                        rows.keys
+                            .iter()
+                            .map(|key| key[index].as_str())
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect(),
+        )?;
+        let df_schema = df.schema();
+        let typed_key_columns = rows
+            .key_columns
+            .iter()
+            .map(|column| {
+                let dtype = df_schema
+                    .get(column)
+                    .with_context(|| format!("key column `{column}` of {table_name} is missing"))?;
+                Ok(typed_key(column, dtype))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let keys = keys
+            .lazy()
+            .select(typed_key_columns)
+            .collect()
+            .with_context(|| format!("could not parse the subset keys of {table_name}"))?;
+
+        let key_columns = rows
+            .key_columns
+            .iter()
+            .map(|column| col(column.as_str()))
+            .collect::<Vec<_>>();
+
+        Ok(df
+            .lazy()
+            .join(
+                keys.lazy(),
+                key_columns.clone(),
+                key_columns,
+                JoinArgs::new(JoinType::Semi),
+            )
+            .collect()?)
+    }
+}
+
+/// Parses the text of a key column to the type of the column in the DataFrames.
+fn typed_key(column: &str, dtype: &DataType) -> Expr {
+    let text = col(column);
+
+    match dtype {
+        DataType::Boolean => text.eq(lit("true")),
+        DataType::Datetime(time_unit, time_zone) => {
+            let strptime = |format: &str, time_zone: Option<TimeZone>| {
+                text.clone().str().to_datetime(
+                    Some(*time_unit),
+                    time_zone,
+                    StrptimeOptions {
+                        format: Some(format.into()),
+                        strict: false,
+                        exact: true,
+                        cache: false,
+                    },
+                    lit("raise"),
+                )
+            };
+            // A `timestamptz` has an offset, a `timestamp` has none. Either may be stored
+            // as a naive Datetime, in UTC.
+            let with_offset = strptime(TIMESTAMPTZ_FORMAT, Some(TimeZone::UTC));
+            let with_offset = match time_zone {
+                Some(time_zone) => with_offset.dt().convert_time_zone(time_zone.clone()),
+                None => with_offset
+                    .dt()
+                    .replace_time_zone(None, lit("raise"), NonExistent::Raise),
+            };
+            let without_offset = strptime(TIMESTAMP_FORMAT, time_zone.clone());
+
+            coalesce(&[with_offset, without_offset]).alias(column)
+        }
+        dtype => text.strict_cast(dtype.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retain_rows_keeps_the_subset_rows() {
+        let mut row_subset = RowSubset::default();
+        let key_columns = vec!["id".to_string()];
+        assert!(row_subset.insert("users", &key_columns, vec!["1".to_string()]));
+        assert!(row_subset.insert("users", &key_columns, vec!["3".to_string()]));
+        assert!(!row_subset.insert("users", &key_columns, vec!["3".to_string()]));
+
+        let users = df!("id" => &[1i64, 2, 3], "name" => &["a", "b", "c"]).unwrap();
+        let users = row_subset.retain_rows("users", users).unwrap();
+
+        let mut ids = users
+            .column("id")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(row_subset.num_of_rows(), vec![("users", 2)]);
+    }
+
+    #[test]
+    fn test_retain_rows_of_composite_keys() {
+        let mut row_subset = RowSubset::default();
+        let key_columns = vec!["user_id".to_string(), "role".to_string()];
+        row_subset.insert(
+            "user_roles",
+            &key_columns,
+            vec!["1".to_string(), "admin".to_string()],
+        );
+
+        let user_roles = df!(
+            "user_id" => &[1i32, 1, 2],
+            "role" => &["admin", "guest", "admin"],
+        )
+        .unwrap();
+        let user_roles = row_subset.retain_rows("user_roles", user_roles).unwrap();
+
+        assert_eq!(user_roles.height(), 1);
+    }
+
+    #[test]
+    fn test_retain_rows_of_timestamp_keys() {
+        let mut row_subset = RowSubset::default();
+        let key_columns = vec!["created_at".to_string()];
+        row_subset.insert(
+            "events",
+            &key_columns,
+            vec!["2024-03-01 10:20:30.5+02".to_string()],
+        );
+        row_subset.insert(
+            "events",
+            &key_columns,
+            vec!["2024-03-02 00:00:00".to_string()],
+        );
+
+        // 2024-03-01 08:20:30.5, 2024-03-01 10:20:30.5 and 2024-03-02 00:00:00, in UTC.
+        let created_at = Series::new(
+            "created_at".into(),
+            [
+                1_709_281_230_500_000i64,
+                1_709_288_430_500_000,
+                1_709_337_600_000_000,
+            ],
+        )
+        .cast(&DataType::Datetime(TimeUnit::Microseconds, None))
+        .unwrap();
+        let events = DataFrame::new(vec![
+            Column::new("id".into(), [1i32, 2, 3]),
+            created_at.into(),
+        ])
+        .unwrap();
+        let events = row_subset.retain_rows("events", events).unwrap();
+
+        assert_eq!(
+            events.column("id").unwrap().i32().unwrap().to_vec(),
+            [Some(1), Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_retain_rows_of_numeric_keys() {
+        let mut row_subset = RowSubset::default();
+        let key_columns = vec!["amount".to_string(), "rate".to_string()];
+        row_subset.insert(
+            "prices",
+            &key_columns,
+            vec!["12.5".to_string(), "0.1".to_string()],
+        );
+
+        let prices = df!(
+            "amount" => &["12.5", "12.05", "12.5"],
+            "rate" => &[0.1f64, 0.1, 0.2],
+        )
+        .unwrap()
+        .lazy()
+        .with_column(col("amount").cast(DataType::Decimal(Some(10), Some(2))))
+        .collect()
+        .unwrap();
+        let prices = row_subset.retain_rows("prices", prices).unwrap();
+
+        assert_eq!(prices.height(), 1);
+        assert_eq!(
+            prices.column("rate").unwrap().f64().unwrap().get(0),
+            Some(0.1)
+        );
+    }
+
+    #[test]
+    fn test_retain_rows_of_tables_outside_the_subset() {
+        let row_subset = RowSubset::default();
+        let countries = df!("code" => &["GR", "UK"]).unwrap();
+
+        assert_eq!(
+            row_subset
+                .retain_rows("countries", countries)
+                .unwrap()
+                .height(),
+            0
+        );
+    }
+}
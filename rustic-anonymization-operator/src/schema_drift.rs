@@ -0,0 +1,241 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+use polars::prelude::{DataFrame, DataType, Series};
+use rustic_anonymization_config::config_structs::schema_drift_policy_struct::SchemaDriftPolicy;
+
+/// Columns added by DMS, which never exist in the source table.
+const DMS_COLUMNS: [&str; 2] = ["Op", "_dms_ingestion_timestamp"];
+
+/// The differences between the columns of a Parquet file and the columns of its source table.
+pub struct SchemaDrift {
+    /// The policy the drift was handled with.
+    pub policy: SchemaDriftPolicy,
+    /// The Parquet columns that were renamed, from the old to the new name.
+    pub renamed_columns: Vec<(String, String)>,
+    /// The Parquet columns that do not exist in the source table.
+    pub extra_columns: Vec<String>,
+    /// The source columns that do not exist in the Parquet file.
+    pub missing_columns: Vec<String>,
+    /// Whether the file can still be written to the target table.
+    pub resolved: bool,
+}
+
+/// A schema drift of a specific Parquet file.
+pub struct SchemaDriftEvent {
+    pub table_name: String,
+    pub file_name: String,
+    pub drift: SchemaDrift,
+}
+
+impl Display for SchemaDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut differences = vec![];
+        if !self.renamed_columns.is_empty() {
+            differences.push(format!(
+                "renamed {}",
+                self.renamed_columns
+                    .iter()
+                    .map(|(from, to)| format!("{from} -> {to}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        if !self.extra_columns.is_empty() {
+            differences.push(format!("extra columns {:?}", self.extra_columns));
+        }
+        if !self.missing_columns.is_empty() {
+            differences.push(format!("missing columns {:?}", self.missing_columns));
+        }
+
+        write!(
+            f,
+            "{} (on_schema_drift: {}, {})",
+            differences.join("; "),
+            self.policy,
+            if self.resolved {
+                "resolved"
+            } else {
+                "unresolved"
+            }
+        )
+    }
+}
+
+impl Display for SchemaDriftEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}",
+            self.table_name, self.file_name, self.drift
+        )
+    }
+}
+
+/// Reconciles the columns of a Parquet file with the columns of its source table.
+///
+/// Columns are renamed first, when the policy is a `rename`. Columns missing from the
+/// source table are then dropped, or fail the file, and source columns missing from the
+/// Parquet file are added as `NULL`, or left to their column default.
+///
+/// # Arguments
+///
+/// * `df` - The Dataframe read from the Parquet file.
+/// * `source_columns` - The columns of the source table.
+/// * `policy` - The schema drift policy of the table.
+///
+/// # Returns
+///
+/// The reconciled Dataframe, along with the drift, if the columns differ at all.
+pub fn reconcile_schema(
+    mut df: DataFrame,
+    source_columns: &[String],
+    policy: &SchemaDriftPolicy,
+) -> Result<(DataFrame, Option<SchemaDrift>)> {
+    let mut renamed_columns = vec![];
+    if let SchemaDriftPolicy::Rename(mapping) = policy {
+        for (from, to) in mapping {
+            if df.get_column_index(from).is_some() {
+                df.rename(from, to.into())?;
+                renamed_columns.push((from.clone(), to.clone()));
+            }
+        }
+    }
+
+    let extra_columns = df
+        .get_column_names()
+        .into_iter()
+        .filter(|column| !DMS_COLUMNS.contains(&column.as_str()))
+        .filter(|column| {
+            !source_columns
+                .iter()
+                .any(|source| source == column.as_str())
+        })
+        .map(|column| column.to_string())
+        .collect::<Vec<_>>();
+    let missing_columns = source_columns
+        .iter()
+        .filter(|column| df.get_column_index(column).is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if renamed_columns.is_empty() && extra_columns.is_empty() && missing_columns.is_empty() {
+        return Ok((df, None));
+    }
+
+    let resolved = match policy {
+        SchemaDriftPolicy::DropExtraColumns => {
+            df = df.drop_many(&extra_columns);
+            true
+        }
+        SchemaDriftPolicy::AddMissingAsNull => {
+            let height = df.height();
+            for column in &missing_columns {
+                df.with_column(Series::full_null(
+                    column.as_str().into(),
+                    height,
+                    &DataType::Null,
+                ))?;
+            }
+            extra_columns.is_empty()
+        }
+        SchemaDriftPolicy::Fail | SchemaDriftPolicy::Rename(_) => extra_columns.is_empty(),
+    };
+
+    Ok((
+        df,
+        Some(SchemaDrift {
+            policy: policy.clone(),
+            renamed_columns,
+            extra_columns,
+            missing_columns,
+            resolved,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use polars::prelude::*;
+
+    use super::*;
+
+    fn source_columns() -> Vec<String> {
+        vec!["id".to_string(), "full_name".to_string()]
+    }
+
+    #[test]
+    fn matching_columns_have_no_drift() {
+        let df = df!(
+            "Op" => ["I"],
+            "id" => [1],
+            "full_name" => ["Geralt"],
+        )
+        .unwrap();
+
+        let (df, drift) =
+            reconcile_schema(df, &source_columns(), &SchemaDriftPolicy::Fail).unwrap();
+
+        assert!(drift.is_none());
+        assert_eq!(df.width(), 3);
+    }
+
+    #[test]
+    fn extra_columns_are_unresolved_unless_dropped() {
+        let df = df!(
+            "id" => [1],
+            "full_name" => ["Geralt"],
+            "nickname" => ["White Wolf"],
+        )
+        .unwrap();
+
+        let (_, drift) =
+            reconcile_schema(df.clone(), &source_columns(), &SchemaDriftPolicy::Fail).unwrap();
+        let drift = drift.unwrap();
+        assert_eq!(drift.extra_columns, vec!["nickname".to_string()]);
+        assert!(!drift.resolved);
+
+        let (df, drift) =
+            reconcile_schema(df, &source_columns(), &SchemaDriftPolicy::DropExtraColumns).unwrap();
+        assert!(drift.unwrap().resolved);
+        assert_eq!(df.get_column_names(), ["id", "full_name"]);
+    }
+
+    #[test]
+    fn missing_columns_are_added_as_null() {
+        let df = df!("id" => [1, 2]).unwrap();
+
+        let (df, drift) =
+            reconcile_schema(df, &source_columns(), &SchemaDriftPolicy::AddMissingAsNull).unwrap();
+
+        let drift = drift.unwrap();
+        assert_eq!(drift.missing_columns, vec!["full_name".to_string()]);
+        assert!(drift.resolved);
+        assert_eq!(df.column("full_name").unwrap().null_count(), 2);
+    }
+
+    #[test]
+    fn renamed_columns_match_the_source() {
+        let df = df!(
+            "id" => [1],
+            "fullname" => ["Geralt"],
+        )
+        .unwrap();
+        let policy = SchemaDriftPolicy::Rename(BTreeMap::from([(
+            "fullname".to_string(),
+            "full_name".to_string(),
+        )]));
+
+        let (df, drift) = reconcile_schema(df, &source_columns(), &policy).unwrap();
+
+        let drift = drift.unwrap();
+        assert_eq!(
+            drift.renamed_columns,
+            vec![("fullname".to_string(), "full_name".to_string())]
+        );
+        assert!(drift.resolved);
+        assert_eq!(df.get_column_names(), ["id", "full_name"]);
+    }
+}
@@ -1,3 +1,29 @@
 pub mod noop_transformator;
 pub mod nulify_transformator;
 pub mod replace_transformator;
+
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator_registry::{TransformatorRegistry, string_param};
+
+use crate::nulify_transformator::NullifyTransformator;
+use crate::replace_transformator::ReplaceTransformator;
+
+/// Registers the `replace` and `nullify` transformators.
+pub fn register_transformators(registry: &mut TransformatorRegistry) {
+    registry
+        .register("replace", |column_name, params| {
+            let replacement_value =
+                string_param(params, "replacement_value")?.ok_or_else(|| {
+                    TransformError::InvalidConfig {
+                        message: "`replace` requires a `replacement_value`".to_string(),
+                    }
+                })?;
+            Ok(Box::new(ReplaceTransformator::new(
+                column_name,
+                replacement_value,
+            )))
+        })
+        .register("nullify", |column_name, _| {
+            Ok(Box::new(NullifyTransformator::new(column_name)))
+        });
+}
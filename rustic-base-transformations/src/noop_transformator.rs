@@ -1,5 +1,6 @@
 use polars::frame::DataFrame;
-use rand::rngs::StdRng;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
 use rustic_transformator::transformator::Transformator;
 use rustic_transformator::transformator_output::TransformatorOutput;
 use rustic_transformator::transformator_type::TransformatorType;
@@ -14,8 +15,12 @@ impl NoOpTransformator {
 }
 
 impl Transformator for NoOpTransformator {
-    fn transform(&self, _: &DataFrame, _: &mut StdRng) -> Vec<TransformatorOutput> {
-        vec![]
+    fn transform(
+        &self,
+        _: &DataFrame,
+        _: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
+        Ok(vec![])
     }
 
     fn transformator_type(&self) -> TransformatorType {
@@ -1,6 +1,7 @@
 use polars::prelude::*;
-use rand::rngs::StdRng;
-use rustic_transformator::transformator::Transformator;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator::{Transformator, input_column, input_dtype};
 use rustic_transformator::transformator_output::TransformatorOutput;
 use rustic_transformator::transformator_type::TransformatorType;
 
@@ -17,23 +18,19 @@ impl NullifyTransformator {
 }
 
 impl Transformator for NullifyTransformator {
-    fn transform(&self, input: &DataFrame, _: &mut StdRng) -> Vec<TransformatorOutput> {
-        let column_name = self.column_name.clone();
-        let col = input.column(&column_name).unwrap();
-        let len = col.len();
-        let dtype = col.dtype();
-
-        let series = match dtype {
-            DataType::String => Series::new(column_name.into(), vec![None::<String>; len]),
-            DataType::Int32 => Series::new(column_name.into(), vec![None::<i32>; len]),
-            DataType::Float64 => Series::new(column_name.into(), vec![None::<f64>; len]),
-            _ => panic!("Unsupported data type: {dtype:?}"),
-        };
-
-        vec![TransformatorOutput {
+    fn transform(
+        &self,
+        input: &DataFrame,
+        _: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
+        let col = input_column(input, &self.column_name)?;
+        // The column keeps its type, whatever it is.
+        let series = Series::full_null((&self.column_name).into(), col.len(), col.dtype());
+
+        Ok(vec![TransformatorOutput {
             column_name: self.column_name.clone(),
             series,
-        }]
+        }])
     }
 
     fn transformator_type(&self) -> TransformatorType {
@@ -41,14 +38,18 @@ impl Transformator for NullifyTransformator {
             column_name: self.column_name.clone(),
         }
     }
+
+    fn transform_expr(&self, schema: &Schema) -> Result<Option<Expr>, TransformError> {
+        let dtype = input_dtype(schema, &self.column_name)?;
+
+        Ok(Some(lit(Null {}).cast(dtype.clone())))
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use rand::SeedableRng;
-    use rand::rngs::StdRng;
 
     #[test]
     fn test_replace_transformator() {
@@ -57,9 +58,9 @@ mod tests {
         ])
         .unwrap();
         let transformator = NullifyTransformator::new("a".to_string());
-        let mut rng = StdRng::seed_from_u64(42);
+        let pseudonymizer = Pseudonymizer::seeded(42);
 
-        let transformed = transformator.transform(&df, &mut rng);
+        let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
 
         assert_eq!(transformed.len(), 1);
         assert_eq!(transformed[0].column_name, "a");
@@ -73,4 +74,42 @@ mod tests {
                 .all(|x| x.is_none())
         );
     }
+
+    #[test]
+    fn test_nullify_keeps_the_column_type() {
+        let date = Series::new("date".into(), &[19_000, 19_001])
+            .cast(&DataType::Date)
+            .unwrap();
+        let binary = Series::new("binary".into(), &[b"ciri".as_slice(), b"geralt".as_slice()]);
+        let df = DataFrame::new(vec![
+            Series::new("id".into(), &[1i64, 2]).into(),
+            Series::new("active".into(), &[true, false]).into(),
+            date.into(),
+            binary.into(),
+        ])
+        .unwrap();
+        let pseudonymizer = Pseudonymizer::seeded(42);
+
+        for column in df.get_columns() {
+            let transformator = NullifyTransformator::new(column.name().as_str());
+
+            let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
+
+            assert_eq!(transformed[0].series.dtype(), column.dtype());
+            assert_eq!(transformed[0].series.null_count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_nullify_expr_keeps_the_column_type() {
+        let df = DataFrame::new(vec![Series::new("a".into(), &[1.5, 2.5]).into()]).unwrap();
+        let transformator = NullifyTransformator::new("a");
+
+        let expr = transformator.transform_expr(df.schema()).unwrap().unwrap();
+        let transformed = df.lazy().with_column(expr.alias("a")).collect().unwrap();
+
+        let a = transformed.column("a").unwrap();
+        assert_eq!(a.dtype(), &DataType::Float64);
+        assert_eq!(a.null_count(), 2);
+    }
 }
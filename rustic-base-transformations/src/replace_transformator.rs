@@ -1,6 +1,7 @@
 use polars::prelude::*;
-use rand::rngs::StdRng;
-use rustic_transformator::transformator::Transformator;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator::{Transformator, input_column, input_dtype};
 use rustic_transformator::transformator_output::TransformatorOutput;
 use rustic_transformator::transformator_type::TransformatorType;
 
@@ -19,18 +20,22 @@ impl ReplaceTransformator {
 }
 
 impl Transformator for ReplaceTransformator {
-    fn transform(&self, input: &DataFrame, _: &mut StdRng) -> Vec<TransformatorOutput> {
-        let fake_values = input.column(&self.column_name).unwrap().len();
+    fn transform(
+        &self,
+        input: &DataFrame,
+        _: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
+        let fake_values = input_column(input, &self.column_name)?.len();
         let fake_values = (0..fake_values)
             .map(|_| self.replacement_value.clone())
             .collect::<Vec<String>>();
 
         let series = Series::new((&self.column_name).into(), fake_values);
 
-        vec![TransformatorOutput {
+        Ok(vec![TransformatorOutput {
             column_name: self.column_name.clone(),
             series,
-        }]
+        }])
     }
 
     fn transformator_type(&self) -> TransformatorType {
@@ -38,22 +43,26 @@ impl Transformator for ReplaceTransformator {
             column_name: self.column_name.clone(),
         }
     }
+
+    fn transform_expr(&self, schema: &Schema) -> Result<Option<Expr>, TransformError> {
+        input_dtype(schema, &self.column_name)?;
+
+        Ok(Some(lit(self.replacement_value.clone())))
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use rand::SeedableRng;
-    use rand::rngs::StdRng;
 
     #[test]
     fn test_replace_transformator() {
         let df = DataFrame::new(vec![Series::new("a".into(), &[1, 2, 3, 4, 5]).into()]).unwrap();
         let transformator = ReplaceTransformator::new("a".to_string(), "test".to_string());
-        let mut rng = StdRng::seed_from_u64(42);
+        let pseudonymizer = Pseudonymizer::seeded(42);
 
-        let transformed = transformator.transform(&df, &mut rng);
+        let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
 
         assert_eq!(transformed.len(), 1);
         assert_eq!(transformed[0].column_name, "a");
@@ -67,4 +76,24 @@ mod tests {
                 .all(|x| x.is_some() && x.unwrap() == "test")
         );
     }
+
+    #[test]
+    fn test_replace_expr_matches_transform() {
+        let df = DataFrame::new(vec![Series::new("a".into(), &[1, 2, 3]).into()]).unwrap();
+        let transformator = ReplaceTransformator::new("a", "test");
+
+        let expr = transformator.transform_expr(df.schema()).unwrap().unwrap();
+        let transformed = df.lazy().with_column(expr.alias("a")).collect().unwrap();
+
+        assert_eq!(
+            transformed
+                .column("a")
+                .unwrap()
+                .str()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            vec!["test", "test", "test"]
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use anyhow::{Context, Result, bail};
 use aws_sdk_s3::Client as S3Client;
 use colored::Colorize;
 use core::panic;
@@ -9,34 +10,196 @@ use dms_cdc_operator::{
     postgres::postgres_operator::{
         InsertDataframePayload, PostgresOperator, UpsertDataframePayload,
     },
-    s3::s3_operator::{LoadParquetFilesPayload, S3Operator, S3OperatorImpl},
+    postgres::table_mode::TableMode,
+    s3::s3_operator::{LoadParquetFilesPayload, S3Operator, S3OperatorImpl, S3ParquetFile},
 };
+use rustic_anonymization_config::config_structs::anonymization_config::AnonymizationConfig;
 use rustic_anonymization_operator::anonymization_dataframe_operator::AnonymizationDataFrameOperator;
+use rustic_anonymization_operator::row_subset::RowSubset;
+use rustic_config_source::config_root::ConfigRoot;
 use rustic_duration::beautify_duration;
+use rustic_run_settings::run_settings::RunSettings;
 use rustic_target_db::prepare_db_config;
 use rustic_target_db::target_db_finalizer::TargetDBFinalizer;
 use rustic_target_db::target_db_preparator::TargetDbPreparator;
-use std::{env, sync::Arc, time::Instant};
-use tracing::info;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::{sync::Arc, time::Instant};
+use tracing::{error, info, warn};
+
+use crate::foreign_keys::{ReferencedKeys, dependency_levels, load_foreign_keys};
+use crate::snapshot_summary::SnapshotSummary;
+use crate::subset::{SubsetSpec, extract_row_subset};
 
 pub struct CDCOperator;
 
 impl CDCOperator {
+    /// Compares every configured table and column of the anonymization configuration against
+    /// the source schema, before anything is dropped in the target DB.
+    ///
+    /// Transformators of columns that do not exist are skipped during the anonymization,
+    /// so a renamed PII column would otherwise be copied in clear text to the target DB.
+    ///
+    /// # Arguments
+    ///
+    /// * `cdc_operator_snapshot_payload` - The payload containing the necessary information for snapshotting.
+    /// * `anonymization_config` - The anonymization configuration of the database and schema.
+    /// * `source_postgres_operator` - The implementation of the PostgresOperator trait for the source database.
+    /// * `registry` - The transformators that can be referred to by name.
+    /// * `run_settings` - The settings of the run.
+    ///
+    /// # Returns
+    ///
+    /// An error if a transformator cannot be built, or if a configured table or column no longer
+    /// exists in the source schema. When the `lenient_schema_preflight` setting is enabled the
+    /// schema problems are only logged as warnings.
+    pub async fn preflight_check(
+        cdc_operator_snapshot_payload: &CDCOperatorSnapshotPayload,
+        anonymization_config: &AnonymizationConfig,
+        source_postgres_operator: &(impl PostgresOperator + Sync),
+        registry: &TransformatorRegistry,
+        run_settings: &RunSettings,
+    ) -> Result<()> {
+        let transformator_problems = anonymization_config.validate_transformators(registry);
+        if !transformator_problems.is_empty() {
+            for problem in &transformator_problems {
+                error!("{problem}");
+            }
+            bail!(
+                "Anonymization configuration has invalid transformators, found {} problem(s)",
+                transformator_problems.len()
+            );
+        }
+
+        info!(
+            "{}",
+            "Validating anonymization configuration against the source schema..."
+                .bold()
+                .blue()
+        );
+
+        let schema_name = cdc_operator_snapshot_payload.schema_name();
+        let schema_tables = source_postgres_operator
+            .get_tables_in_schema(schema_name.as_str(), &[], &[], &TableMode::AllTables)
+            .await?;
+
+        // Table names may be patterns, so every source table is resolved
+        // against the configuration, which also reports the pattern it matched.
+        let mut source_tables = HashMap::new();
+        for table_name in &schema_tables {
+            if anonymization_config
+                .fetch_table_config(table_name)
+                .is_none()
+            {
+                continue;
+            }
+
+            let source_table_columns = source_postgres_operator
+                .get_table_columns(schema_name.as_str(), table_name.as_str())
+                .await?;
+            source_tables.insert(
+                table_name.clone(),
+                source_table_columns.into_keys().collect::<Vec<_>>(),
+            );
+        }
+
+        let problems = anonymization_config.validate_against_source(&source_tables);
+
+        if problems.is_empty() {
+            info!("Anonymization configuration matches the source schema");
+            return Ok(());
+        }
+
+        if run_settings.lenient_schema_preflight {
+            for problem in &problems {
+                warn!("{problem}");
+            }
+            return Ok(());
+        }
+
+        for problem in &problems {
+            error!("{problem}");
+        }
+
+        bail!(
+            "Anonymization configuration does not match the source schema, found {} problem(s)",
+            problems.len()
+        )
+    }
+
+    /// Finds the rows of a subset extraction in the source database, before anything
+    /// is dropped in the target DB.
+    ///
+    /// # Arguments
+    ///
+    /// * `cdc_operator_snapshot_payload` - The payload containing the necessary information for snapshotting.
+    /// * `subset_spec` - The root rows and the depth of the subset.
+    /// * `run_settings` - The settings of the run.
+    ///
+    /// # Returns
+    ///
+    /// The rows of every table of the subset, or an error if a root table or column does not exist.
+    pub async fn extract_subset(
+        cdc_operator_snapshot_payload: &CDCOperatorSnapshotPayload,
+        subset_spec: &SubsetSpec,
+        run_settings: &RunSettings,
+    ) -> Result<RowSubset> {
+        info!(
+            "{}",
+            format!(
+                "Extracting subset of depth {} around: {}",
+                subset_spec.depth,
+                subset_spec
+                    .roots
+                    .iter()
+                    .map(|root| root.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )
+            .bold()
+            .blue()
+        );
+
+        let schema_name = cdc_operator_snapshot_payload.schema_name();
+        let source_cfg = prepare_db_config(
+            cdc_operator_snapshot_payload.source_postgres_url(),
+            run_settings,
+        );
+        let source_pool = source_cfg.create_pool(Some(Runtime::Tokio1), NoTls)?;
+        let foreign_keys = load_foreign_keys(&source_pool, schema_name.as_str()).await?;
+        let client = source_pool.get().await?;
+
+        extract_row_subset(&client, schema_name.as_str(), subset_spec, &foreign_keys).await
+    }
+
     /// Prepares for a snapshot by taking a pg_dump of the source DB, dropping the schema in the target DB,
     /// restoring the schema in the target DB, creating a super-user for data import, and importing sequences last values.
     ///
     /// # Arguments
     ///
     /// * `cdc_operator_snapshot_payload` - The payload containing the necessary information for snapshotting.
-    pub async fn prepare_for_snapshot(cdc_operator_snapshot_payload: &CDCOperatorSnapshotPayload) {
+    /// * `config_root` - The directory containing the configuration files.
+    /// * `run_settings` - The settings of the run.
+    pub async fn prepare_for_snapshot(
+        cdc_operator_snapshot_payload: &CDCOperatorSnapshotPayload,
+        config_root: &ConfigRoot,
+        run_settings: &RunSettings,
+    ) {
         // Prepare source DB configuration
-        let source_cfg = prepare_db_config(cdc_operator_snapshot_payload.source_postgres_url());
+        let source_cfg = prepare_db_config(
+            cdc_operator_snapshot_payload.source_postgres_url(),
+            run_settings,
+        );
         let source_pool = source_cfg
             .create_pool(Some(Runtime::Tokio1), NoTls)
             .unwrap();
 
         // Prepare target DB configuration
-        let target_cfg = prepare_db_config(cdc_operator_snapshot_payload.target_postgres_url());
+        let target_cfg = prepare_db_config(
+            cdc_operator_snapshot_payload.target_postgres_url(),
+            run_settings,
+        );
         let target_pool = target_cfg
             .create_pool(Some(Runtime::Tokio1), NoTls)
             .unwrap();
@@ -44,6 +207,7 @@ impl CDCOperator {
         let target_db_preparator = TargetDbPreparator {
             target_db_pool: target_pool,
             source_db_pool: source_pool,
+            run_settings: run_settings.clone(),
         };
 
         info!("{}", "Taking a pg_dump of the source DB".bold().blue());
@@ -87,6 +251,7 @@ impl CDCOperator {
         info!("{}", "Fixing sequences ownership...".bold().blue());
         target_db_preparator
             .fix_sequences_ownership(
+                config_root,
                 cdc_operator_snapshot_payload.database_name().as_str(),
                 cdc_operator_snapshot_payload.schema_name().as_str(),
             )
@@ -98,20 +263,34 @@ impl CDCOperator {
     /// # Arguments
     ///
     /// * `cdc_operator_snapshot_payload` - The payload containing the necessary information for snapshotting.
+    /// * `anonymization_config` - The anonymization configuration of the database and schema.
     /// * `source_postgres_operator` - The implementation of the PostgresOperator trait for the source database.
     /// * `superuser_postgres_operator` - The implementation of the PostgresOperator trait for the target database.
     /// * `s3_client` - The S3 client for accessing the Parquet files.
+    /// * `row_subset` - The rows to keep, when a subset is extracted. Tables outside of it are skipped.
+    /// * `registry` - The transformators that can be referred to by name.
+    /// * `run_settings` - The settings of the run.
+    ///
+    /// # Returns
+    ///
+    /// The summary of the snapshot, with the tables that failed and every schema drift found,
+    /// or an error if a transformator of the anonymization configuration could not be built.
+    #[allow(clippy::too_many_arguments)]
     pub async fn snapshot(
         cdc_operator_snapshot_payload: &CDCOperatorSnapshotPayload,
+        anonymization_config: &AnonymizationConfig,
         source_postgres_operator: &(impl PostgresOperator + Sync),
         superuser_postgres_operator: &(impl PostgresOperator + Sync),
         s3_client: &S3Client,
-    ) {
+        row_subset: Option<Arc<RowSubset>>,
+        registry: &TransformatorRegistry,
+        run_settings: &RunSettings,
+    ) -> Result<SnapshotSummary> {
         info!("{}", "Starting snapshotting...".bold().blue());
 
         // Find tables that will be included in the snapshotting operation
         let get_tables_in_schema_start = Instant::now();
-        let table_list = source_postgres_operator
+        let mut table_list = source_postgres_operator
             .get_tables_in_schema(
                 cdc_operator_snapshot_payload.schema_name().as_str(),
                 cdc_operator_snapshot_payload.included_tables().as_slice(),
@@ -126,237 +305,399 @@ impl CDCOperator {
             get_tables_in_schema_duration
         );
 
+        if let Some(row_subset) = &row_subset {
+            table_list.retain(|table| row_subset.contains_table(table));
+            info!("Tables of the subset: {:?}", table_list);
+        }
+
+        // Resolve the anonymization configuration of every table once,
+        // so it is shared by all the tables below.
+        let resolve_config_start = Instant::now();
+        let mut source_tables = HashMap::new();
+        for table in &table_list {
+            let source_table_columns = source_postgres_operator
+                .get_table_columns(
+                    cdc_operator_snapshot_payload.schema_name().as_str(),
+                    table.as_str(),
+                )
+                .await
+                .with_context(|| format!("could not read the columns of {table}"))?;
+            source_tables.insert(
+                table.clone(),
+                source_table_columns.into_keys().collect::<Vec<_>>(),
+            );
+        }
+        let resolved_anonymization_config =
+            Arc::new(AnonymizationDataFrameOperator::resolve_config(
+                anonymization_config,
+                &source_tables,
+                registry,
+            )?);
+        info!(
+            "Resolve anonymization config duration: {}",
+            beautify_duration(resolve_config_start.elapsed())
+        );
+
+        // When the records are reduced consistently with the foreign keys, the
+        // referenced tables are written first, so that the rows referencing rows
+        // which were not kept can be dropped.
+        let (table_levels, referenced_keys) = if run_settings.is_foreign_key_consistent_reduction()
+        {
+            let source_cfg = prepare_db_config(
+                cdc_operator_snapshot_payload.source_postgres_url(),
+                run_settings,
+            );
+            let source_pool = source_cfg
+                .create_pool(Some(Runtime::Tokio1), NoTls)
+                .context("could not connect to the source database")?;
+            let foreign_keys = load_foreign_keys(
+                &source_pool,
+                cdc_operator_snapshot_payload.schema_name().as_str(),
+            )
+            .await
+            .context("could not load the foreign keys of the source schema")?;
+            info!(
+                "Loaded {} foreign key(s) of the source schema",
+                foreign_keys.len()
+            );
+
+            (
+                dependency_levels(&table_list, &foreign_keys),
+                Some(ReferencedKeys::new(&table_list, foreign_keys)),
+            )
+        } else {
+            (vec![table_list.clone()], None)
+        };
+        let referenced_keys = referenced_keys.as_ref();
+
         // Prepare [Arc]s for usage in multi threaded operations below.
         let cdc_operator_snapshot_payload: Arc<&CDCOperatorSnapshotPayload> =
             Arc::new(cdc_operator_snapshot_payload);
         let client = s3_client.clone();
         let s3_operator = Arc::new(S3OperatorImpl::new(&client));
-        let dataframe_operator = Arc::new(AnonymizationDataFrameOperator::new(s3_client));
-
-        let anonymized_tables = table_list
-            .iter()
-            .map(|table| {
-                let payload = cdc_operator_snapshot_payload.clone();
-                let s3_operator = s3_operator.clone();
-                let dataframe_operator = dataframe_operator.clone();
-
-                async move {
-                    let payload = payload.clone();
-                    let start = Instant::now();
-                    info!(
-                        "{}",
-                        format!("Running for table: {table}").bold().magenta()
-                    );
-
-                    // Get the table columns
-                    info!("{}", "Getting table columns".bold().green());
-                    let get_table_columns_start = Instant::now();
-                    let source_table_columns: indexmap::IndexMap<String, String> =
-                        source_postgres_operator
-                            .get_table_columns(payload.schema_name().as_str(), table.as_str())
-                            .await
-                            .unwrap();
-                    let get_table_columns_duration =
-                        beautify_duration(get_table_columns_start.elapsed());
-                    info!("Get table columns duration: {}", get_table_columns_duration);
-                    info!(
-                        "Number of columns: {}, Columns: {:?}",
-                        source_table_columns.len(),
-                        source_table_columns
-                    );
-
-                    // Get the primary key for the table
-                    info!("{}", "Getting primary key".bold().green());
-                    let get_primary_key_start = Instant::now();
-                    let primary_key_list = source_postgres_operator
-                        .get_primary_key(table.as_str(), payload.schema_name().as_str())
-                        .await
-                        .unwrap();
-                    let get_primary_key_duration =
-                        beautify_duration(get_primary_key_start.elapsed());
-                    info!("Get primary keys duration: {}", get_primary_key_duration);
-                    info!("Primary key(s): {:?}", primary_key_list);
-
-                    // Get the list of Parquet files from S3 that are related to the table
-                    info!("{}", "Getting list of Parquet files from S3".bold().green());
-
-                    // Check if mode is DateAware and start_date is not None
-                    if payload.mode_is_date_aware() && payload.start_date().is_none() {
-                        panic!("start_date is required for DateAware mode");
-                    }
+        let dataframe_operator = AnonymizationDataFrameOperator::new(
+            s3_client,
+            resolved_anonymization_config,
+            run_settings,
+        );
+        let dataframe_operator = Arc::new(match row_subset {
+            Some(row_subset) => dataframe_operator.with_row_subset(row_subset),
+            None => dataframe_operator,
+        });
+        let failed_tables = Mutex::new(vec![]);
+        let failed_tables = &failed_tables;
 
-                    let load_parquet_files_payload = if payload.mode_is_date_aware() {
-                        LoadParquetFilesPayload::DateAware {
-                            bucket_name: payload.bucket_name().clone(),
-                            s3_prefix: payload.key().clone(),
-                            database_name: payload.database_name().clone(),
-                            schema_name: payload.schema_name().clone(),
-                            table_name: table.to_string(),
-                            start_date: payload.start_date().clone().unwrap(),
-                            stop_date: payload.stop_date().clone(),
-                        }
-                    } else if payload.mode_is_full_load_only() {
-                        LoadParquetFilesPayload::FullLoadOnly {
-                            bucket_name: payload.bucket_name().clone(),
-                            s3_prefix: payload.key().clone(),
-                            database_name: payload.database_name().clone(),
-                            schema_name: payload.schema_name().clone(),
-                            table_name: table.to_string(),
-                        }
-                    } else {
-                        LoadParquetFilesPayload::AbsolutePath(payload.key().clone())
-                    };
-
-                    let get_parquet_files_start = Instant::now();
-                    let parquet_files = s3_operator
-                        .get_list_of_parquet_files_from_s3(&load_parquet_files_payload)
-                        .await;
-                    let get_parquet_files_duration =
-                        beautify_duration(get_parquet_files_start.elapsed());
-                    info!(
-                        "Get parquet files from S3 duration: {}",
-                        get_parquet_files_duration
-                    );
-
-                    // For each `.parquet` file in S3, we create a Dataframe
-                    // that will be inserted in the target database, after getting
-                    // anonymized.
-                    info!("{}", "Reading Parquet files from S3".bold().green());
-                    for file in &parquet_files.unwrap() {
-                        let create_dataframe_payload = CreateDataframePayload {
-                            bucket_name: payload.bucket_name().clone(),
-                            key: file.file_name.to_string(),
-                            database_name: payload.database_name().clone(),
-                            schema_name: payload.schema_name().clone(),
-                            table_name: table.clone(),
-                        };
+        use futures::FutureExt;
+        use futures::StreamExt;
+        use futures::stream::{self};
 
-                        let create_df_start = Instant::now();
-                        let current_df = dataframe_operator
-                            .create_dataframe_from_parquet_file(&create_dataframe_payload)
+        for (level, tables) in table_levels.iter().enumerate() {
+            if table_levels.len() > 1 {
+                info!(
+                    "{}",
+                    format!(
+                        "Snapshotting tables of level {}/{}: {tables:?}",
+                        level + 1,
+                        table_levels.len()
+                    )
+                    .bold()
+                    .blue()
+                );
+            }
+
+            let anonymized_tables = tables
+                .iter()
+                .map(|table| {
+                    let payload = cdc_operator_snapshot_payload.clone();
+                    let s3_operator = s3_operator.clone();
+                    let dataframe_operator = dataframe_operator.clone();
+
+                    async move {
+                        let payload = payload.clone();
+                        let start = Instant::now();
+                        let foreign_keys = referenced_keys
+                            .map(|referenced_keys| referenced_keys.enforced_foreign_keys(table))
+                            .unwrap_or_default();
+                        info!("{}", format!("Running for table: {table}").bold().magenta());
+
+                        // Get the table columns
+                        info!("{}", "Getting table columns".bold().green());
+                        let get_table_columns_start = Instant::now();
+                        let source_table_columns: indexmap::IndexMap<String, String> =
+                            source_postgres_operator
+                                .get_table_columns(payload.schema_name().as_str(), table.as_str())
+                                .await
+                                .unwrap();
+                        let get_table_columns_duration =
+                            beautify_duration(get_table_columns_start.elapsed());
+                        info!("Get table columns duration: {}", get_table_columns_duration);
+                        info!(
+                            "Number of columns: {}, Columns: {:?}",
+                            source_table_columns.len(),
+                            source_table_columns
+                        );
+
+                        // Get the primary key for the table
+                        info!("{}", "Getting primary key".bold().green());
+                        let get_primary_key_start = Instant::now();
+                        let primary_key_list = source_postgres_operator
+                            .get_primary_key(table.as_str(), payload.schema_name().as_str())
                             .await
-                            .map_err(|e| {
-                                panic!("Error reading Parquet file: {:?}", e);
-                            })
                             .unwrap();
-                        let create_df_duration = beautify_duration(create_df_start.elapsed());
-                        info!("Creating DF for table {table} took: {create_df_duration}");
+                        let get_primary_key_duration =
+                            beautify_duration(get_primary_key_start.elapsed());
+                        info!("Get primary keys duration: {}", get_primary_key_duration);
+                        info!("Primary key(s): {:?}", primary_key_list);
 
-                        let current_df = if let Some(current_df) = current_df {
-                            current_df
-                        } else {
-                            continue;
-                        };
+                        // Get the list of Parquet files from S3 that are related to the table
+                        info!("{}", "Getting list of Parquet files from S3".bold().green());
 
-                        // This branch will operate on LOAD `.parquet` files,
-                        // whereas the next one is responsible for the CDC files.
-                        if file.is_load_file() {
-                            info!("Processing LOAD file: {:?}", file);
-                            // Check if the schema of the table is the same as the schema of the Parquet file
-                            // in case of altered column names or dropped columns
-                            let df_column_fields = current_df.get_columns();
-
-                            let has_schema_diff = df_column_fields
-                                .iter()
-                                .filter(|field| {
-                                    field.name() != "Op"
-                                        && field.name() != "_dms_ingestion_timestamp"
-                                })
-                                .any(|field| !source_table_columns.contains_key(field.name().as_str()));
-
-                            // Early exit if we detect a schema change. In order to mitigate that,
-                            // we will trigger a new full load through DMS.
-                            if has_schema_diff {
-                                panic!(
-                                    "Schema of table is not the same as the schema of the Parquet file"
-                                );
-                            }
+                        // Check if mode is DateAware and start_date is not None
+                        if payload.mode_is_date_aware() && payload.start_date().is_none() {
+                            panic!("start_date is required for DateAware mode");
+                        }
 
-                            // Prepare for the insertion of the Dataframe in the target
-                            // database.
-                            let insert_dataframe_payload = InsertDataframePayload {
+                        let load_parquet_files_payload = if payload.mode_is_date_aware() {
+                            LoadParquetFilesPayload::DateAware {
+                                bucket_name: payload.bucket_name().clone(),
+                                s3_prefix: payload.key().clone(),
                                 database_name: payload.database_name().clone(),
                                 schema_name: payload.schema_name().clone(),
-                                table_name: table.clone(),
-                            };
-                            let insert_dataframe_start = Instant::now();
-                            let insert_result = superuser_postgres_operator
-                                .insert_dataframe_in_target_db(
-                                    &current_df,
-                                    &insert_dataframe_payload,
-                                )
-                                .await;
-
-                            match insert_result {
-                                Ok(_) => {
-                                    info!("Successfully inserted LOAD file into table");
-                                }
-                                Err(e) => {
-                                    panic!(
-                                        "Failed to insert LOAD file into table -> {}: {:?}",
-                                        table.clone(),
-                                        e
-                                    );
-                                }
+                                table_name: table.to_string(),
+                                start_date: payload.start_date().clone().unwrap(),
+                                stop_date: payload.stop_date().clone(),
+                            }
+                        } else if payload.mode_is_full_load_only() {
+                            LoadParquetFilesPayload::FullLoadOnly {
+                                bucket_name: payload.bucket_name().clone(),
+                                s3_prefix: payload.key().clone(),
+                                database_name: payload.database_name().clone(),
+                                schema_name: payload.schema_name().clone(),
+                                table_name: table.to_string(),
                             }
-                            let insert_dataframe_duration =
-                                beautify_duration(insert_dataframe_start.elapsed());
-                            info!(
-                                "Insert DF {} duration: {}",
-                                table, insert_dataframe_duration
-                            );
                         } else {
-                            info!("Processing CDC file: {:?}", file);
-                            let primary_keys = primary_key_list.join(",");
+                            LoadParquetFilesPayload::AbsolutePath(payload.key().clone())
+                        };
 
-                            let upsert_dataframe_payload = UpsertDataframePayload {
+                        let get_parquet_files_start = Instant::now();
+                        let parquet_files = s3_operator
+                            .get_list_of_parquet_files_from_s3(&load_parquet_files_payload)
+                            .await;
+                        let get_parquet_files_duration =
+                            beautify_duration(get_parquet_files_start.elapsed());
+                        info!(
+                            "Get parquet files from S3 duration: {}",
+                            get_parquet_files_duration
+                        );
+
+                        // For each `.parquet` file in S3, we create a Dataframe
+                        // that will be inserted in the target database, after getting
+                        // anonymized.
+                        info!("{}", "Reading Parquet files from S3".bold().green());
+                        let parquet_files = parquet_files.unwrap();
+                        let create_dataframe_payload =
+                            |file: &S3ParquetFile| CreateDataframePayload {
+                                bucket_name: payload.bucket_name().clone(),
+                                key: file.file_name.to_string(),
                                 database_name: payload.database_name().clone(),
                                 schema_name: payload.schema_name().clone(),
                                 table_name: table.clone(),
-                                primary_key: primary_keys.clone(),
                             };
 
-                            superuser_postgres_operator
-                                .upsert_dataframe_in_target_db(
-                                    &current_df,
-                                    &upsert_dataframe_payload,
-                                )
+                        // The records of a reduced table are picked across all its
+                        // LOAD files, before any of them is written.
+                        let load_files = parquet_files
+                            .iter()
+                            .filter(|file| file.is_load_file())
+                            .map(create_dataframe_payload)
+                            .collect::<Vec<_>>();
+                        let mut failed = false;
+                        if let Err(e) = dataframe_operator
+                            .sample_table_records(table, &load_files)
+                            .await
+                        {
+                            error!("Error sampling the records of {table}: {:?}", e);
+                            failed_tables.lock().unwrap().push((
+                                table.clone(),
+                                format!("could not sample its records: {e:#}"),
+                            ));
+                            failed = true;
+                        }
+
+                        for file in &parquet_files {
+                            if failed {
+                                break;
+                            }
+                            let create_dataframe_payload = create_dataframe_payload(file);
+
+                            let create_df_start = Instant::now();
+                            let current_df = match dataframe_operator
+                                .create_dataframe_from_parquet_file(&create_dataframe_payload)
                                 .await
-                                .unwrap_or_else(|_| {
-                                    panic!("Failed to upsert CDC file {:?} into table", file)
-                                });
+                            {
+                                Ok(current_df) => current_df,
+                                Err(e) => {
+                                    error!("Error reading Parquet file {:?}: {:?}", file, e);
+                                    failed_tables
+                                        .lock()
+                                        .unwrap()
+                                        .push((table.clone(), format!("{e:#}")));
+                                    failed = true;
+                                    break;
+                                }
+                            };
+                            let create_df_duration = beautify_duration(create_df_start.elapsed());
+                            info!("Creating DF for table {table} took: {create_df_duration}");
+
+                            let current_df = if let Some(current_df) = current_df {
+                                current_df
+                            } else {
+                                continue;
+                            };
+
+                            // Drop the rows referencing rows that were not kept.
+                            let current_df = match referenced_keys {
+                                Some(referenced_keys) => match referenced_keys
+                                    .retain_referencing_rows(table, current_df, &foreign_keys)
+                                {
+                                    Ok(current_df) => current_df,
+                                    Err(e) => {
+                                        error!(
+                                            "Error filtering {:?} by its foreign keys: {:?}",
+                                            file, e
+                                        );
+                                        failed_tables.lock().unwrap().push((
+                                            table.clone(),
+                                            format!("could not filter by its foreign keys: {e:#}"),
+                                        ));
+                                        failed = true;
+                                        break;
+                                    }
+                                },
+                                None => current_df,
+                            };
+
+                            // This branch will operate on LOAD `.parquet` files,
+                            // whereas the next one is responsible for the CDC files.
+                            if file.is_load_file() {
+                                info!("Processing LOAD file: {:?}", file);
+
+                                // Prepare for the insertion of the Dataframe in the target
+                                // database.
+                                let insert_dataframe_payload = InsertDataframePayload {
+                                    database_name: payload.database_name().clone(),
+                                    schema_name: payload.schema_name().clone(),
+                                    table_name: table.clone(),
+                                };
+                                let insert_dataframe_start = Instant::now();
+                                let insert_result = superuser_postgres_operator
+                                    .insert_dataframe_in_target_db(
+                                        &current_df,
+                                        &insert_dataframe_payload,
+                                    )
+                                    .await;
+
+                                match insert_result {
+                                    Ok(_) => {
+                                        info!("Successfully inserted LOAD file into table");
+                                    }
+                                    Err(e) => {
+                                        panic!(
+                                            "Failed to insert LOAD file into table -> {}: {:?}",
+                                            table.clone(),
+                                            e
+                                        );
+                                    }
+                                }
+                                let insert_dataframe_duration =
+                                    beautify_duration(insert_dataframe_start.elapsed());
+                                info!(
+                                    "Insert DF {} duration: {}",
+                                    table, insert_dataframe_duration
+                                );
+                            } else {
+                                info!("Processing CDC file: {:?}", file);
+                                let primary_keys = primary_key_list.join(",");
+
+                                let upsert_dataframe_payload = UpsertDataframePayload {
+                                    database_name: payload.database_name().clone(),
+                                    schema_name: payload.schema_name().clone(),
+                                    table_name: table.clone(),
+                                    primary_key: primary_keys.clone(),
+                                };
+
+                                superuser_postgres_operator
+                                    .upsert_dataframe_in_target_db(
+                                        &current_df,
+                                        &upsert_dataframe_payload,
+                                    )
+                                    .await
+                                    .unwrap_or_else(|_| {
+                                        panic!("Failed to upsert CDC file {:?} into table", file)
+                                    });
+                            }
+
+                            if let Some(Err(e)) = referenced_keys.map(|referenced_keys| {
+                                referenced_keys.record_written_rows(table, &current_df)
+                            }) {
+                                error!("Error keeping the keys of {:?}: {:?}", file, e);
+                                failed_tables.lock().unwrap().push((
+                                    table.clone(),
+                                    format!("could not keep its keys: {e:#}"),
+                                ));
+                                failed = true;
+                                break;
+                            }
+
+                            drop(current_df);
                         }
 
-                        drop(current_df);
-                    }
+                        drop(s3_operator);
+                        drop(dataframe_operator);
 
-                    drop(s3_operator);
-                    drop(dataframe_operator);
+                        // The rows of a failed table are incomplete, so the tables
+                        // referencing it are not filtered by its keys.
+                        if failed {
+                            error!(
+                                "{}",
+                                format!("Snapshot failed for table {table}").red().bold()
+                            );
+                            if let Some(referenced_keys) = referenced_keys {
+                                referenced_keys.release_keys(table);
+                            }
+                            return;
+                        }
 
-                    let elapsed = beautify_duration(start.elapsed());
+                        if let Some(referenced_keys) = referenced_keys {
+                            referenced_keys.mark_completed(table);
+                        }
 
-                    info!(
-                        "{}",
-                        format!("Snapshot completed for table {table} in: {elapsed}")
-                            .yellow()
-                            .bold(),
-                    );
-                }
-            })
-            .collect::<Vec<_>>();
+                        let elapsed = beautify_duration(start.elapsed());
 
-        use futures::FutureExt;
-        use futures::StreamExt;
-        use futures::stream::{self};
+                        info!(
+                            "{}",
+                            format!("Snapshot completed for table {table} in: {elapsed}")
+                                .yellow()
+                                .bold(),
+                        );
+                    }
+                })
+                .collect::<Vec<_>>();
 
-        let stream = stream::iter(anonymized_tables)
-            .map(|future| future.boxed())
-            .buffer_unordered(num_of_buffers());
+            let stream = stream::iter(anonymized_tables)
+                .map(|future| future.boxed())
+                .buffer_unordered(run_settings.num_of_buffers);
 
-        // Collect results, ensuring at most [num_of_buffers()] futures run concurrently
-        stream.for_each(|_| async {}).await;
+            // Collect results, ensuring at most [num_of_buffers] futures run concurrently
+            stream.for_each(|_| async {}).await;
+        }
 
         info!("{}", "Snapshotting completed...".bold().blue());
+
+        Ok(SnapshotSummary {
+            failed_tables: failed_tables.lock().unwrap().drain(..).collect(),
+            schema_drift_events: dataframe_operator.take_schema_drift_events(),
+        })
     }
 
     /// Finalizes the snapshot by updating the sequences in the target database.
@@ -369,15 +710,18 @@ impl CDCOperator {
     /// * `application_users` - Any application users to be granted with permissions,
     ///   in the target database.
     /// * `app_owner` - The database owner role (extracted from TARGET_POSTGRES_URL).
+    /// * `run_settings` - The settings of the run.
     pub async fn finalize_snapshot(
         target_pool: Pool,
         database_name: &str,
         schema_name: &str,
         application_users: Vec<String>,
         app_owner: &str,
+        run_settings: &RunSettings,
     ) {
         let target_db_finalizer = TargetDBFinalizer::builder()
             .target_db_pool(target_pool)
+            .run_settings(run_settings.clone())
             .build();
 
         // Update sequence values
@@ -401,12 +745,3 @@ impl CDCOperator {
             .await;
     }
 }
-
-// Controls the number of max parallel processed
-// Dataframes for insertion.
-fn num_of_buffers() -> usize {
-    env::var("NUM_OF_BUFFERS")
-        .unwrap_or_else(|_| "80".to_string())
-        .parse::<usize>()
-        .unwrap()
-}
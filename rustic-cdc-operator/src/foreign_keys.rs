@@ -0,0 +1,460 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use anyhow::{Result, anyhow};
+use deadpool_postgres::Pool;
+use polars::prelude::*;
+use tracing::{info, warn};
+
+// Lists the foreign keys between the tables of a schema, along with
+// their columns in the order of the constraint.
+const FOREIGN_KEYS_QUERY: &str = r#"
+    SELECT
+        con.conname::text AS constraint_name,
+        child.relname::text AS child_table,
+        array_agg(child_attribute.attname::text ORDER BY key.ordinality) AS child_columns,
+        parent.relname::text AS parent_table,
+        array_agg(parent_attribute.attname::text ORDER BY key.ordinality) AS parent_columns
+    FROM pg_constraint con
+    JOIN pg_class child ON child.oid = con.conrelid
+    JOIN pg_class parent ON parent.oid = con.confrelid
+    JOIN pg_namespace child_namespace ON child_namespace.oid = child.relnamespace
+    JOIN pg_namespace parent_namespace ON parent_namespace.oid = parent.relnamespace
+    CROSS JOIN LATERAL unnest(con.conkey, con.confkey)
+        WITH ORDINALITY AS key(child_attnum, parent_attnum, ordinality)
+    JOIN pg_attribute child_attribute
+        ON child_attribute.attrelid = con.conrelid AND child_attribute.attnum = key.child_attnum
+    JOIN pg_attribute parent_attribute
+        ON parent_attribute.attrelid = con.confrelid AND parent_attribute.attnum = key.parent_attnum
+    WHERE con.contype = 'f'
+        AND child_namespace.nspname = $1
+        AND parent_namespace.nspname = $1
+    GROUP BY con.conname, child.relname, parent.relname
+    ORDER BY child.relname, con.conname
+"#;
+
+/// A foreign key between two tables of the source schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKey {
+    pub constraint_name: String,
+    pub child_table: String,
+    pub child_columns: Vec<String>,
+    pub parent_table: String,
+    pub parent_columns: Vec<String>,
+}
+
+impl ForeignKey {
+    fn is_self_reference(&self) -> bool {
+        self.child_table == self.parent_table
+    }
+
+    fn id(&self) -> ForeignKeyId {
+        (self.child_table.clone(), self.constraint_name.clone())
+    }
+}
+
+/// Reads the foreign keys between the tables of a schema of the source database.
+pub async fn load_foreign_keys(
+    source_db_pool: &Pool,
+    schema_name: &str,
+) -> Result<Vec<ForeignKey>> {
+    let client = source_db_pool.get().await?;
+    let rows = client.query(FOREIGN_KEYS_QUERY, &[&schema_name]).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ForeignKey {
+            constraint_name: row.get("constraint_name"),
+            child_table: row.get("child_table"),
+            child_columns: row.get("child_columns"),
+            parent_table: row.get("parent_table"),
+            parent_columns: row.get("parent_columns"),
+        })
+        .collect())
+}
+
+/// Groups the tables in levels, so that every table comes after the tables it references.
+///
+/// The tables of a level only reference tables of the previous levels, so they can be
+/// processed concurrently. Self references are ignored, and the tables that are part of
+/// a reference cycle are placed in a last level.
+pub fn dependency_levels(tables: &[String], foreign_keys: &[ForeignKey]) -> Vec<Vec<String>> {
+    let run_tables = tables.iter().collect::<HashSet<_>>();
+    let mut parents: HashMap<&String, HashSet<&String>> = HashMap::new();
+    for foreign_key in foreign_keys {
+        if !foreign_key.is_self_reference()
+            && run_tables.contains(&foreign_key.child_table)
+            && run_tables.contains(&foreign_key.parent_table)
+        {
+            parents
+                .entry(&foreign_key.child_table)
+                .or_default()
+                .insert(&foreign_key.parent_table);
+        }
+    }
+
+    let mut levels = vec![];
+    let mut done = HashSet::new();
+    let mut remaining = tables.iter().collect::<Vec<_>>();
+
+    while !remaining.is_empty() {
+        let (level, rest): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|table| {
+            parents
+                .get(table)
+                .is_none_or(|parents| parents.iter().all(|parent| done.contains(parent)))
+        });
+
+        if level.is_empty() {
+            warn!(
+                "Tables {rest:?} reference each other, their foreign keys cannot all be kept consistent"
+            );
+            levels.push(rest.into_iter().cloned().collect());
+            break;
+        }
+
+        done.extend(level.iter().copied());
+        levels.push(level.into_iter().cloned().collect());
+        remaining = rest;
+    }
+
+    levels
+}
+
+/// Keeps track of the keys of the rows written to the referenced tables of a run,
+/// so that the rows of the tables referencing them can be filtered down to the ones
+/// whose referenced rows were kept.
+///
+/// Only the referenced columns of every foreign key are kept in memory, once per distinct
+/// key, until the referencing table is written. The memory cost is then about the number
+/// of distinct keys written to a referenced table times the width of its key, for every
+/// foreign key whose referencing table is not written yet.
+pub struct ReferencedKeys {
+    foreign_keys: Vec<ForeignKey>,
+    /// The distinct referenced keys of the written rows, per foreign key.
+    keys: Mutex<HashMap<ForeignKeyId, DataFrame>>,
+    /// The tables whose rows have all been written.
+    completed_tables: Mutex<HashSet<String>>,
+}
+
+/// Identifies a foreign key, as constraint names are only unique within a table.
+type ForeignKeyId = (String, String);
+
+impl ReferencedKeys {
+    /// Keeps track of the foreign keys between the tables of a run.
+    pub fn new(tables: &[String], foreign_keys: Vec<ForeignKey>) -> Self {
+        let foreign_keys = foreign_keys
+            .into_iter()
+            .filter(|foreign_key| {
+                !foreign_key.is_self_reference()
+                    && tables.contains(&foreign_key.child_table)
+                    && tables.contains(&foreign_key.parent_table)
+            })
+            .collect();
+
+        Self {
+            foreign_keys,
+            keys: Mutex::new(HashMap::new()),
+            completed_tables: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Returns the foreign keys of a table whose referenced tables are already written.
+    ///
+    /// The rest of its foreign keys, e.g. self references or reference cycles, are logged.
+    pub fn enforced_foreign_keys(&self, table_name: &str) -> Vec<ForeignKey> {
+        let completed_tables = self.completed_tables.lock().unwrap();
+        let (enforced, not_enforced): (Vec<_>, Vec<_>) = self
+            .foreign_keys
+            .iter()
+            .filter(|foreign_key| foreign_key.child_table == table_name)
+            .cloned()
+            .partition(|foreign_key| completed_tables.contains(&foreign_key.parent_table));
+
+        for foreign_key in not_enforced {
+            warn!(
+                "{table_name}: `{}` is not kept consistent, as `{}` is not written yet",
+                foreign_key.constraint_name, foreign_key.parent_table
+            );
+        }
+
+        enforced
+    }
+
+    /// Drops the rows that reference rows which were not written to their referenced table.
+    /// Rows with a `NULL` in any column of a foreign key do not reference any row and are kept.
+    pub fn retain_referencing_rows(
+        &self,
+        table_name: &str,
+        df: DataFrame,
+        foreign_keys: &[ForeignKey],
+    ) -> Result<DataFrame> {
+        let mut df = df;
+
+        for foreign_key in foreign_keys {
+            let num_of_rows = df.height();
+            let parent_keys = self.parent_keys(foreign_key, df.schema())?;
+
+            let has_null = foreign_key
+                .child_columns
+                .iter()
+                .map(|column| col(column.as_str()).is_null())
+                .reduce(Expr::or)
+                .ok_or_else(|| anyhow!("`{}` has no columns", foreign_key.constraint_name))?;
+            let key_columns = foreign_key
+                .child_columns
+                .iter()
+                .map(|column| col(column.as_str()))
+                .collect::<Vec<_>>();
+
+            let without_reference = df.clone().lazy().filter(has_null.clone());
+            let with_reference = df.lazy().filter(has_null.not()).join(
+                parent_keys.lazy(),
+                key_columns.clone(),
+                key_columns,
+                JoinArgs::new(JoinType::Semi),
+            );
+            df = concat([without_reference, with_reference], UnionArgs::default())?.collect()?;
+
+            let dropped = num_of_rows - df.height();
+            if dropped > 0 {
+                info!(
+                    "{table_name}: dropped {dropped} row(s) referencing rows of `{}` that were not kept",
+                    foreign_key.parent_table
+                );
+            }
+        }
+
+        Ok(df)
+    }
+
+    /// Returns the keys written to the referenced table of a foreign key, named and typed
+    /// as the referencing columns of `child_schema`.
+    fn parent_keys(&self, foreign_key: &ForeignKey, child_schema: &Schema) -> Result<DataFrame> {
+        let key_columns = foreign_key
+            .parent_columns
+            .iter()
+            .zip(&foreign_key.child_columns)
+            .map(|(parent_column, child_column)| {
+                let dtype = child_schema.get(child_column).ok_or_else(|| {
+                    anyhow!(
+                        "column `{child_column}` of `{}` is missing",
+                        foreign_key.constraint_name
+                    )
+                })?;
+                Ok(col(parent_column.as_str())
+                    .strict_cast(dtype.clone())
+                    .alias(child_column.as_str()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let keys = self.keys.lock().unwrap();
+        let parent_keys = match keys.get(&foreign_key.id()) {
+            Some(parent_keys) => parent_keys.clone(),
+            // No rows were written to the referenced table.
+            None => DataFrame::new(
+                foreign_key
+                    .parent_columns
+                    .iter()
+                    .map(|column| Column::new_empty(column.as_str().into(), &DataType::Null))
+                    .collect(),
+            )?,
+        };
+        drop(keys);
+
+        Ok(parent_keys.lazy().select(key_columns).collect()?)
+    }
+
+    /// Keeps the distinct keys of the rows written to a table, for every foreign key
+    /// referencing it from a table that is not written yet.
+    pub fn record_written_rows(&self, table_name: &str, df: &DataFrame) -> Result<()> {
+        let completed_tables = self.completed_tables.lock().unwrap().clone();
+
+        for foreign_key in self.foreign_keys.iter().filter(|foreign_key| {
+            foreign_key.parent_table == table_name
+                && !completed_tables.contains(&foreign_key.child_table)
+        }) {
+            let written_keys = df.select(foreign_key.parent_columns.iter().map(String::as_str))?;
+
+            let mut keys = self.keys.lock().unwrap();
+            let written_keys = match keys.remove(&foreign_key.id()) {
+                Some(keys) => keys.vstack(&written_keys)?,
+                None => written_keys,
+            };
+            let written_keys = written_keys
+                .lazy()
+                .unique(None, UniqueKeepStrategy::Any)
+                .collect()?;
+            keys.insert(foreign_key.id(), written_keys);
+        }
+
+        Ok(())
+    }
+
+    /// Marks all the rows of a table as written, so that the tables referencing it
+    /// can be filtered against its keys.
+    pub fn mark_completed(&self, table_name: &str) {
+        self.completed_tables
+            .lock()
+            .unwrap()
+            .insert(table_name.to_string());
+        self.release_keys(table_name);
+    }
+
+    /// Drops the keys kept for the foreign keys of a table, once it is done with them,
+    /// whether it was written or it failed.
+    pub fn release_keys(&self, table_name: &str) {
+        self.keys
+            .lock()
+            .unwrap()
+            .retain(|(child_table, _), _| child_table != table_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn foreign_key(child_table: &str, child_column: &str, parent_table: &str) -> ForeignKey {
+        ForeignKey {
+            constraint_name: format!("{child_table}_{child_column}_fkey"),
+            child_table: child_table.to_string(),
+            child_columns: vec![child_column.to_string()],
+            parent_table: parent_table.to_string(),
+            parent_columns: vec!["id".to_string()],
+        }
+    }
+
+    fn tables(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dependency_levels_order_parents_first() {
+        let foreign_keys = vec![
+            foreign_key("bookings", "user_id", "users"),
+            foreign_key("bookings", "listing_id", "listings"),
+            foreign_key("listings", "owner_id", "users"),
+            foreign_key("users", "referrer_id", "users"),
+            foreign_key("payments", "booking_id", "bookings"),
+        ];
+
+        let levels = dependency_levels(
+            &tables(&["payments", "bookings", "listings", "users", "countries"]),
+            &foreign_keys,
+        );
+
+        assert_eq!(
+            levels,
+            vec![
+                tables(&["users", "countries"]),
+                tables(&["listings"]),
+                tables(&["bookings"]),
+                tables(&["payments"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dependency_levels_put_cycles_last() {
+        let foreign_keys = vec![
+            foreign_key("a", "b_id", "b"),
+            foreign_key("b", "a_id", "a"),
+            foreign_key("c", "a_id", "a"),
+        ];
+
+        let levels = dependency_levels(&tables(&["a", "b", "c", "d"]), &foreign_keys);
+
+        assert_eq!(levels, vec![tables(&["d"]), tables(&["a", "b", "c"])]);
+    }
+
+    #[test]
+    fn test_retain_referencing_rows_drops_orphans() {
+        let referenced_keys = ReferencedKeys::new(
+            &tables(&["users", "bookings"]),
+            vec![foreign_key("bookings", "user_id", "users")],
+        );
+        let users = df!("id" => &[1i32, 2], "name" => &["a", "b"]).unwrap();
+        referenced_keys
+            .record_written_rows("users", &users)
+            .unwrap();
+        referenced_keys.mark_completed("users");
+
+        let foreign_keys = referenced_keys.enforced_foreign_keys("bookings");
+        assert_eq!(foreign_keys.len(), 1);
+
+        let bookings = df!(
+            "id" => &[10, 11, 12, 13],
+            "user_id" => &[Some(1i64), Some(3), None, Some(2)],
+        )
+        .unwrap();
+        let bookings = referenced_keys
+            .retain_referencing_rows("bookings", bookings, &foreign_keys)
+            .unwrap();
+
+        let mut ids = bookings
+            .column("id")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![10, 12, 13]);
+    }
+
+    #[test]
+    fn test_retain_referencing_rows_without_written_parent_rows() {
+        let referenced_keys = ReferencedKeys::new(
+            &tables(&["users", "bookings"]),
+            vec![foreign_key("bookings", "user_id", "users")],
+        );
+        referenced_keys.mark_completed("users");
+
+        let bookings = df!("id" => &[10, 11], "user_id" => &[Some(1i64), None]).unwrap();
+        let bookings = referenced_keys
+            .retain_referencing_rows(
+                "bookings",
+                bookings,
+                &referenced_keys.enforced_foreign_keys("bookings"),
+            )
+            .unwrap();
+
+        assert_eq!(bookings.height(), 1);
+    }
+
+    #[test]
+    fn test_keys_are_distinct_and_released_once_the_referencing_table_is_written() {
+        let referenced_keys = ReferencedKeys::new(
+            &tables(&["users", "bookings"]),
+            vec![foreign_key("bookings", "user_id", "users")],
+        );
+        let users = df!("id" => &[1i32, 2, 2], "name" => &["a", "b", "c"]).unwrap();
+        referenced_keys
+            .record_written_rows("users", &users)
+            .unwrap();
+        referenced_keys
+            .record_written_rows("users", &users)
+            .unwrap();
+
+        let id = foreign_key("bookings", "user_id", "users").id();
+        let keys = referenced_keys.keys.lock().unwrap()[&id].clone();
+        assert_eq!(keys.get_column_names(), ["id"]);
+        assert_eq!(keys.height(), 2);
+
+        referenced_keys.mark_completed("bookings");
+        assert!(referenced_keys.keys.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_foreign_keys_of_unwritten_tables_are_not_enforced() {
+        let referenced_keys = ReferencedKeys::new(
+            &tables(&["users", "bookings"]),
+            vec![
+                foreign_key("bookings", "user_id", "users"),
+                foreign_key("bookings", "listing_id", "listings"),
+            ],
+        );
+
+        assert!(referenced_keys.enforced_foreign_keys("bookings").is_empty());
+    }
+}
@@ -1 +1,4 @@
 pub mod cdc_operator;
+pub mod foreign_keys;
+pub mod snapshot_summary;
+pub mod subset;
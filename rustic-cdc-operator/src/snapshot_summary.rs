@@ -0,0 +1,37 @@
+use colored::Colorize;
+use rustic_anonymization_operator::schema_drift::SchemaDriftEvent;
+use tracing::{error, info, warn};
+
+/// The outcome of a snapshot, reported once every table is processed.
+#[derive(Default)]
+pub struct SnapshotSummary {
+    /// The tables that could not be snapshotted, along with the reason.
+    pub failed_tables: Vec<(String, String)>,
+    /// Every schema drift found in the Parquet files, resolved or not.
+    pub schema_drift_events: Vec<SchemaDriftEvent>,
+}
+
+impl SnapshotSummary {
+    pub fn has_failed_tables(&self) -> bool {
+        !self.failed_tables.is_empty()
+    }
+
+    /// Logs the failed tables and the schema drifts of the snapshot.
+    pub fn log(&self) {
+        info!("{}", "Snapshot summary".bold().blue());
+
+        if self.schema_drift_events.is_empty() {
+            info!("No schema drift found");
+        }
+        for event in &self.schema_drift_events {
+            warn!("Schema drift in {event}");
+        }
+
+        if self.failed_tables.is_empty() {
+            info!("All tables were snapshotted");
+        }
+        for (table_name, reason) in &self.failed_tables {
+            error!("{}", format!("Table {table_name} failed: {reason}").red());
+        }
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use deadpool_postgres::Object;
+use deadpool_postgres::tokio_postgres::types::ToSql;
+use indexmap::IndexSet;
+use rustic_anonymization_operator::row_subset::RowSubset;
+use tracing::info;
+
+use crate::foreign_keys::ForeignKey;
+
+// Lists the columns of a table, along with their type and
+// their position in the primary key, if any.
+const TABLE_COLUMNS_QUERY: &str = r#"
+    SELECT
+        a.attname::text AS column_name,
+        format_type(a.atttypid, a.atttypmod) AS column_type,
+        array_position(i.indkey::int2[], a.attnum) AS key_position
+    FROM pg_attribute a
+    LEFT JOIN pg_index i ON i.indrelid = a.attrelid AND i.indisprimary
+    WHERE a.attrelid = (quote_ident($1) || '.' || quote_ident($2))::regclass
+        AND a.attnum > 0
+        AND NOT a.attisdropped
+    ORDER BY a.attnum
+"#;
+
+/// The root rows of a subset extraction, e.g. `users.id=123,456`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsetRoot {
+    pub table_name: String,
+    pub column_name: String,
+    pub values: Vec<String>,
+}
+
+impl FromStr for SubsetRoot {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || format!("`{value}` is not in the `<table>.<column>=<value>[,<value>...]` form");
+
+        let (column, values) = value.split_once('=').ok_or_else(invalid)?;
+        let (table_name, column_name) = column.trim().split_once('.').ok_or_else(invalid)?;
+        let values = values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        if table_name.is_empty() || column_name.is_empty() || values.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(SubsetRoot {
+            table_name: table_name.to_string(),
+            column_name: column_name.to_string(),
+            values,
+        })
+    }
+}
+
+impl Display for SubsetRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}={}",
+            self.table_name,
+            self.column_name,
+            self.values.join(",")
+        )
+    }
+}
+
+/// What a subset extraction keeps: the root rows, and the rows reachable from them
+/// by following the foreign keys of the source schema, in both directions.
+#[derive(Debug, Clone)]
+pub struct SubsetSpec {
+    pub roots: Vec<SubsetRoot>,
+    /// How many foreign keys are followed from the root rows.
+    pub depth: usize,
+}
+
+/// The columns of a source table that a subset extraction needs.
+struct SubsetTable {
+    column_types: HashMap<String, String>,
+    /// The columns identifying a row: the primary key, or every fetched column without one.
+    key_columns: Vec<String>,
+    /// The key columns and the columns of the foreign keys of the table.
+    fetched_columns: Vec<String>,
+}
+
+/// The rows of a table whose columns are equal to one of the specified values.
+#[derive(Debug, PartialEq)]
+struct RowLookup {
+    table_name: String,
+    columns: Vec<String>,
+    /// The distinct values, in the order they were found.
+    values: IndexSet<Vec<String>>,
+}
+
+/// Finds the rows of a subset in the source database.
+///
+/// # Arguments
+///
+/// * `client` - A client of the source database.
+/// * `schema_name` - The schema of the tables.
+/// * `subset_spec` - The root rows and the depth of the subset.
+/// * `foreign_keys` - The foreign keys of the schema.
+///
+/// # Returns
+///
+/// The rows of every table of the subset, or an error if a root table or column does not exist.
+pub async fn extract_row_subset(
+    client: &Object,
+    schema_name: &str,
+    subset_spec: &SubsetSpec,
+    foreign_keys: &[ForeignKey],
+) -> Result<RowSubset> {
+    let mut row_subset = RowSubset::default();
+    let mut tables: HashMap<String, SubsetTable> = HashMap::new();
+    let mut lookups = subset_spec
+        .roots
+        .iter()
+        .map(|root| RowLookup {
+            table_name: root.table_name.clone(),
+            columns: vec![root.column_name.clone()],
+            values: root
+                .values
+                .iter()
+                .map(|value| vec![value.clone()])
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    for depth in 0..=subset_spec.depth {
+        let mut next_lookups: Vec<RowLookup> = vec![];
+
+        for lookup in &lookups {
+            if !tables.contains_key(&lookup.table_name) {
+                let table =
+                    subset_table(client, schema_name, &lookup.table_name, foreign_keys).await?;
+                tables.insert(lookup.table_name.clone(), table);
+            }
+            let table = &tables[&lookup.table_name];
+
+            let rows = fetch_rows(client, schema_name, table, lookup).await?;
+            let new_rows = rows
+                .into_iter()
+                .filter(|row| {
+                    let key = table
+                        .key_columns
+                        .iter()
+                        .map(|column| row[column].clone().unwrap_or_default())
+                        .collect();
+                    row_subset.insert(&lookup.table_name, &table.key_columns, key)
+                })
+                .collect::<Vec<_>>();
+
+            if depth == subset_spec.depth || new_rows.is_empty() {
+                continue;
+            }
+
+            for foreign_key in foreign_keys {
+                if foreign_key.child_table == lookup.table_name {
+                    add_lookup(
+                        &mut next_lookups,
+                        &foreign_key.parent_table,
+                        &foreign_key.parent_columns,
+                        &foreign_key.child_columns,
+                        &new_rows,
+                    );
+                }
+                if foreign_key.parent_table == lookup.table_name {
+                    add_lookup(
+                        &mut next_lookups,
+                        &foreign_key.child_table,
+                        &foreign_key.child_columns,
+                        &foreign_key.parent_columns,
+                        &new_rows,
+                    );
+                }
+            }
+        }
+
+        lookups = next_lookups;
+    }
+
+    for (table_name, num_of_rows) in row_subset.num_of_rows() {
+        info!("Subset of {table_name}: {num_of_rows} row(s)");
+    }
+
+    Ok(row_subset)
+}
+
+/// Adds a lookup of the rows of a table whose `columns` are equal to the `value_columns`
+/// of the specified rows, merging it with an existing lookup of the same columns.
+fn add_lookup(
+    lookups: &mut Vec<RowLookup>,
+    table_name: &str,
+    columns: &[String],
+    value_columns: &[String],
+    rows: &[HashMap<String, Option<String>>],
+) {
+    // Rows with a `NULL` in any column do not reference any row.
+    let values = rows.iter().filter_map(|row| {
+        value_columns
+            .iter()
+            .map(|column| row[column].clone())
+            .collect::<Option<Vec<_>>>()
+    });
+
+    let lookup = match lookups
+        .iter()
+        .position(|lookup| lookup.table_name == table_name && lookup.columns == columns)
+    {
+        Some(index) => &mut lookups[index],
+        None => {
+            lookups.push(RowLookup {
+                table_name: table_name.to_string(),
+                columns: columns.to_vec(),
+                values: IndexSet::new(),
+            });
+            lookups.last_mut().unwrap()
+        }
+    };
+
+    lookup.values.extend(values);
+}
+
+async fn subset_table(
+    client: &Object,
+    schema_name: &str,
+    table_name: &str,
+    foreign_keys: &[ForeignKey],
+) -> Result<SubsetTable> {
+    let rows = client
+        .query(TABLE_COLUMNS_QUERY, &[&schema_name, &table_name])
+        .await?;
+
+    let column_types = rows
+        .iter()
+        .map(|row| (row.get("column_name"), row.get("column_type")))
+        .collect::<HashMap<String, String>>();
+
+    let mut primary_key = rows
+        .iter()
+        .filter_map(|row| {
+            row.get::<_, Option<i32>>("key_position")
+                .map(|position| (position, row.get::<_, String>("column_name")))
+        })
+        .collect::<Vec<_>>();
+    primary_key.sort_unstable();
+    let primary_key = primary_key
+        .into_iter()
+        .map(|(_, column)| column)
+        .collect::<Vec<_>>();
+
+    let mut fetched_columns = primary_key.clone();
+    for foreign_key in foreign_keys {
+        if foreign_key.child_table == table_name {
+            fetched_columns.extend(foreign_key.child_columns.iter().cloned());
+        }
+        if foreign_key.parent_table == table_name {
+            fetched_columns.extend(foreign_key.parent_columns.iter().cloned());
+        }
+    }
+    let mut seen = HashSet::new();
+    fetched_columns.retain(|column| seen.insert(column.clone()));
+
+    // A table without a primary key or foreign keys is identified by all its columns.
+    if fetched_columns.is_empty() {
+        fetched_columns = rows.iter().map(|row| row.get("column_name")).collect();
+    }
+
+    let key_columns = if primary_key.is_empty() {
+        fetched_columns.clone()
+    } else {
+        primary_key
+    };
+
+    Ok(SubsetTable {
+        column_types,
+        key_columns,
+        fetched_columns,
+    })
+}
+
+async fn fetch_rows(
+    client: &Object,
+    schema_name: &str,
+    table: &SubsetTable,
+    lookup: &RowLookup,
+) -> Result<Vec<HashMap<String, Option<String>>>> {
+    if lookup.values.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let query = lookup_query(schema_name, table, lookup)?;
+    let params = (0..lookup.columns.len())
+        .map(|index| {
+            lookup
+                .values
+                .iter()
+                .map(|value| value[index].clone())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let params = params
+        .iter()
+        .map(|param| param as &(dyn ToSql + Sync))
+        .collect::<Vec<_>>();
+
+    let rows = client.query(&query, &params).await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            table
+                .fetched_columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| (column.clone(), row.get(index)))
+                .collect()
+        })
+        .collect())
+}
+
+/// Builds the query of a lookup. The values are passed as text arrays,
+/// cast to the types of the columns so that their indexes can be used.
+fn lookup_query(schema_name: &str, table: &SubsetTable, lookup: &RowLookup) -> Result<String> {
+    let selected_columns = table
+        .fetched_columns
+        .iter()
+        .map(|column| format!("{}::text", quote_ident(column)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let lookup_columns = lookup
+        .columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let lookup_values = lookup
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let column_type = table.column_types.get(column).ok_or_else(|| {
+                anyhow!(
+                    "column `{column}` does not exist in table `{}`",
+                    lookup.table_name
+                )
+            })?;
+            Ok(format!("${}::text[]::{column_type}[]", index + 1))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(", ");
+
+    Ok(format!(
+        "SELECT {selected_columns} FROM {}.{} WHERE ({lookup_columns}) IN (SELECT * FROM unnest({lookup_values}))",
+        quote_ident(schema_name),
+        quote_ident(&lookup.table_name),
+    ))
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_subset_root() {
+        let root: SubsetRoot = "users.id=123, 456".parse().unwrap();
+
+        assert_eq!(
+            root,
+            SubsetRoot {
+                table_name: "users".to_string(),
+                column_name: "id".to_string(),
+                values: strings(&["123", "456"]),
+            }
+        );
+        assert_eq!(root.to_string(), "users.id=123,456");
+    }
+
+    #[test]
+    fn test_parse_invalid_subset_roots() {
+        for value in ["users=1", "users.id", ".id=1", "users.=1", "users.id="] {
+            assert!(
+                value.parse::<SubsetRoot>().is_err(),
+                "{value} should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn test_add_lookup_merges_values_and_skips_nulls() {
+        let rows = vec![
+            HashMap::from([("user_id".to_string(), Some("1".to_string()))]),
+            HashMap::from([("user_id".to_string(), None)]),
+            HashMap::from([("user_id".to_string(), Some("2".to_string()))]),
+        ];
+        let mut lookups = vec![];
+
+        add_lookup(
+            &mut lookups,
+            "users",
+            &strings(&["id"]),
+            &strings(&["user_id"]),
+            &rows,
+        );
+        add_lookup(
+            &mut lookups,
+            "users",
+            &strings(&["id"]),
+            &strings(&["user_id"]),
+            &rows[..1],
+        );
+
+        assert_eq!(
+            lookups,
+            vec![RowLookup {
+                table_name: "users".to_string(),
+                columns: strings(&["id"]),
+                values: IndexSet::from([strings(&["1"]), strings(&["2"])]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lookup_query_casts_values_to_the_column_types() {
+        let table = SubsetTable {
+            column_types: HashMap::from([
+                ("user_id".to_string(), "integer".to_string()),
+                ("role".to_string(), "character varying(32)".to_string()),
+            ]),
+            key_columns: strings(&["user_id", "role"]),
+            fetched_columns: strings(&["user_id", "role"]),
+        };
+        let lookup = RowLookup {
+            table_name: "user_roles".to_string(),
+            columns: strings(&["user_id", "role"]),
+            values: IndexSet::from([strings(&["1", "admin"])]),
+        };
+
+        assert_eq!(
+            lookup_query("public", &table, &lookup).unwrap(),
+            "SELECT \"user_id\"::text, \"role\"::text FROM \"public\".\"user_roles\" \
+             WHERE (\"user_id\", \"role\") IN \
+             (SELECT * FROM unnest($1::text[]::integer[], $2::text[]::character varying(32)[]))"
+        );
+    }
+
+    #[test]
+    fn test_lookup_query_of_unknown_column_fails() {
+        let table = SubsetTable {
+            column_types: HashMap::new(),
+            key_columns: strings(&["id"]),
+            fetched_columns: strings(&["id"]),
+        };
+        let lookup = RowLookup {
+            table_name: "users".to_string(),
+            columns: strings(&["missing"]),
+            values: IndexSet::from([strings(&["1"])]),
+        };
+
+        assert!(lookup_query("public", &table, &lookup).is_err());
+    }
+}
@@ -1,38 +1,49 @@
 use anyhow::Result;
+use clap::Parser;
 use console::style;
 use rustic_anonymization_config::config_structs::anonymization_config::AnonymizationConfig;
 use rustic_anonymization_config::config_structs::column_transformation_struct::AnonymizationColumnTransformation;
+use rustic_anonymization_config::config_structs::custom_operation_type_struct::CustomOperationType;
+use rustic_anonymization_config::config_structs::name_match_type_struct::NameMatchType;
+use rustic_anonymization_config::config_structs::sampling_type_struct::SamplingType;
 use rustic_anonymization_config::config_structs::table_struct::AnonymizationConfigTable;
 use rustic_anonymization_config::config_structs::table_type_struct::AnonymizationConfigTableType;
 use rustic_anonymization_config::config_structs::transformation_type_struct::AnonymizationTransformationType;
-use rustic_faker_types::FakerType;
+use rustic_config_source::config_root::ConfigRoot;
 use std::fs::{self, OpenOptions};
 use std::io::prelude::*;
+use std::path::PathBuf;
 use strum::IntoEnumIterator;
 
 use cliclack::{intro, outro, outro_note};
 
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Directory containing the configuration files
+    #[arg(
+        long,
+        env = "RUSTIC_CONFIG_DIR",
+        default_value = "../configuration_data"
+    )]
+    config_dir: PathBuf,
+}
+
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let config_root = ConfigRoot::new(cli.config_dir);
+
     ctrlc::set_handler(move || {}).expect("setting Ctrl-C handler");
 
     cliclack::clear_screen()?;
 
-    let available_configurations = fs::read_dir("../configuration_data")?;
+    let available_configurations = fs::read_dir(config_root.path())?;
 
     let mut available_configurations = available_configurations
         .filter_map(|entry| {
-            entry.ok().and_then(|path| {
-                if path.file_name().into_string().ok()?.ends_with(".toml") {
-                    Some(path.path().display().to_string())
-                } else {
-                    None
-                }
-            })
-        })
-        .map(|path| {
-            let path = path.replace("../configuration_data/", "");
-            let path = path.replace("-sync.toml", "");
-            (path.clone(), path, String::new())
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            let configuration = file_name.strip_suffix("-sync.toml")?.to_string();
+            Some((configuration.clone(), configuration, String::new()))
         })
         .collect::<Vec<_>>();
 
@@ -65,16 +76,14 @@ fn main() -> Result<()> {
             )
             .interact()?;
 
-    let custom_operation: Option<String> = if operation_type == "custom" {
-        Some(build_custom_faker_type_selection())
+    let custom_operation: Option<CustomOperationType> = if operation_type == "custom" {
+        Some(build_custom_operation_type_selection()?)
     } else {
         None
     };
 
     let anonymization_transformation_type = if let Some(custom_operation) = custom_operation {
-        AnonymizationTransformationType::Custom {
-            operation_type: custom_operation,
-        }
+        AnonymizationTransformationType::Custom(custom_operation)
     } else {
         AnonymizationTransformationType::Replace {
             replacement_value: cliclack::input("Enter the replacement value:").interact()?,
@@ -85,10 +94,19 @@ fn main() -> Result<()> {
         column_name,
         transformation_type: anonymization_transformation_type,
         retain_if_empty: None,
+        domain: None,
+        on_null: None,
+        on_empty: None,
+        cast: None,
+        when: None,
     };
 
     // Read the current configuration to check if we have a configuration entry for the selected table
-    let config_file_name = format!("../configuration_data/{configuration}-sync.toml");
+    let config_file_name = config_root
+        .path()
+        .join(format!("{configuration}-sync.toml"))
+        .display()
+        .to_string();
     let read_conf = fs::read_to_string(config_file_name.as_str());
     let mut config_under_edit = match read_conf {
         Ok(conf) => match toml::from_str(&conf) {
@@ -132,10 +150,13 @@ fn main() -> Result<()> {
 
     let anonymization_config_table = AnonymizationConfigTable {
         table_name: table_name.clone(),
+        match_type: NameMatchType::Exact,
         anonymization_type: anonymization_config_table_type,
         keep_num_of_records: None,
+        sampling: SamplingType::Head,
         filter_type: None,
         sanitize_null_bytes: None,
+        on_schema_drift: None,
     };
 
     // Drop table if it exists
@@ -170,18 +191,30 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn build_custom_faker_type_selection() -> String {
-    let items_for_faker = FakerType::iter()
-        .map(|faker_type| {
-            let value = faker_type.to_string();
-            let label = format!("{faker_type:?}");
-            (value, label, "")
-        })
+fn build_custom_operation_type_selection() -> Result<CustomOperationType> {
+    let items_for_operation_types = CustomOperationType::iter()
+        .map(|operation_type| (operation_type.name(), operation_type.name(), ""))
         .collect::<Vec<_>>();
-    let items_for_faker = items_for_faker.as_slice();
 
-    cliclack::select("Select Faker operation:")
-        .items(items_for_faker)
-        .interact()
-        .unwrap()
+    let selected_name = cliclack::select("Select Faker operation:")
+        .items(items_for_operation_types.as_slice())
+        .interact()?;
+
+    let operation_type = CustomOperationType::iter()
+        .find(|operation_type| operation_type.name() == selected_name)
+        .expect("selected operation type should exist");
+
+    // Ask for the parameters of the operation types that accept any
+    let operation_type = match operation_type {
+        CustomOperationType::FakeEmailWithIdPrefixTransformation { .. } => {
+            CustomOperationType::FakeEmailWithIdPrefixTransformation {
+                id_column: cliclack::input("Which column holds the id prefix?")
+                    .default_input("id")
+                    .interact()?,
+            }
+        }
+        operation_type => operation_type,
+    };
+
+    Ok(operation_type)
 }
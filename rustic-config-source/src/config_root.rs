@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tempfile::TempDir;
+
+/// The default local configuration directory, relative to the working directory.
+pub const DEFAULT_CONFIG_DIR: &str = "configuration_data";
+
+/// A local directory containing all the configuration files.
+///
+/// Every loader resolves the paths of its files through this struct,
+/// so they all read from the same place.
+#[derive(Debug, Clone)]
+pub struct ConfigRoot {
+    path: PathBuf,
+    /// Keeps a downloaded configuration alive; it is deleted once the last clone is dropped.
+    _temp_dir: Option<Arc<TempDir>>,
+}
+
+impl ConfigRoot {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _temp_dir: None,
+        }
+    }
+
+    /// A configuration directory owned by the run, such as one downloaded from S3.
+    pub fn temporary(temp_dir: TempDir) -> Self {
+        Self {
+            path: temp_dir.path().to_path_buf(),
+            _temp_dir: Some(Arc::new(temp_dir)),
+        }
+    }
+
+    /// The configuration directory itself.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The anonymization configuration of a database and schema.
+    pub fn anonymization_config_path(&self, database_name: &str, schema_name: &str) -> PathBuf {
+        self.path
+            .join(format!("{database_name}-{schema_name}-sync.toml"))
+    }
+
+    /// The anonymization configuration shared by every database and schema.
+    pub fn common_config_path(&self) -> PathBuf {
+        self.path.join("common.toml")
+    }
+
+    /// The directory of the validations configurations.
+    pub fn validations_dir(&self) -> PathBuf {
+        self.path.join("validations")
+    }
+
+    /// The validations configuration of a database and schema.
+    pub fn validations_config_path(&self, database_name: &str, schema_name: &str) -> PathBuf {
+        self.validations_dir()
+            .join(format!("{database_name}-{schema_name}.toml"))
+    }
+
+    /// The sequence ownership fixes of a database and schema.
+    pub fn sequences_fix_path(&self, database_name: &str, schema_name: &str) -> PathBuf {
+        self.path
+            .join("sequences_fix")
+            .join(format!("{database_name}-{schema_name}"))
+            .join("ownerships.txt")
+    }
+
+    /// A file listing the tables to include in a run.
+    pub fn inclusions_path(&self, file_name: &str) -> PathBuf {
+        self.path.join("inclusions").join(file_name)
+    }
+}
+
+impl PartialEq for ConfigRoot {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl Default for ConfigRoot {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONFIG_DIR)
+    }
+}
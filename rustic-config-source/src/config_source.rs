@@ -0,0 +1,198 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use aws_sdk_s3::Client as S3Client;
+use tracing::info;
+
+use crate::config_root::ConfigRoot;
+
+const S3_SCHEME: &str = "s3://";
+
+/// Where the configuration files are read from.
+///
+/// Parsed from either a local directory path or an `s3://bucket/prefix` URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    /// A local directory.
+    Local(PathBuf),
+    /// Every object under `prefix` in `bucket`, keeping their paths relative to `prefix`.
+    S3 { bucket: String, prefix: String },
+}
+
+impl ConfigSource {
+    /// Makes the configuration available as a local directory.
+    ///
+    /// A local source is used as is. An S3 source is downloaded to a new temporary directory first,
+    /// which is deleted once the returned `ConfigRoot` is dropped.
+    ///
+    /// # Returns
+    ///
+    /// The `ConfigRoot` all configuration files should be read from.
+    pub async fn materialize(&self) -> Result<ConfigRoot> {
+        match self {
+            ConfigSource::Local(path) => Ok(ConfigRoot::new(path)),
+            ConfigSource::S3 { bucket, prefix } => {
+                let s3_client = rustic_s3_config::create_s3_client().await;
+                let local_dir = tempfile::Builder::new()
+                    .prefix("rustic-witcher-config-")
+                    .tempdir()
+                    .context("Failed to create a temporary configuration directory")?;
+
+                download_prefix(&s3_client, bucket, prefix, local_dir.path()).await?;
+
+                Ok(ConfigRoot::temporary(local_dir))
+            }
+        }
+    }
+}
+
+/// Downloads every object under `prefix` into `local_dir`.
+async fn download_prefix(
+    s3_client: &S3Client,
+    bucket: &str,
+    prefix: &str,
+    local_dir: &Path,
+) -> Result<()> {
+    info!("Downloading configuration from s3://{bucket}/{prefix} to {local_dir:?}");
+
+    // List `prefix/` rather than `prefix`, so that `configs/prod` does not match `configs/prod-old`.
+    let list_prefix = if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix}/")
+    };
+
+    let mut pages = s3_client
+        .list_objects_v2()
+        .bucket(bucket)
+        .prefix(&list_prefix)
+        .into_paginator()
+        .send();
+
+    let mut downloaded_files = 0;
+    while let Some(page) = pages.next().await {
+        let page = page.with_context(|| format!("Failed to list s3://{bucket}/{prefix}"))?;
+
+        for key in page.contents().iter().filter_map(|object| object.key()) {
+            let relative_path = key.strip_prefix(&list_prefix).unwrap_or(key);
+            // Skip "directory" placeholder objects.
+            if relative_path.is_empty() || key.ends_with('/') {
+                continue;
+            }
+
+            let local_path = local_dir.join(
+                local_relative_path(relative_path)
+                    .with_context(|| format!("Refusing to download s3://{bucket}/{key}"))?,
+            );
+
+            let object = s3_client
+                .get_object()
+                .bucket(bucket)
+                .key(key)
+                .send()
+                .await
+                .with_context(|| format!("Failed to download s3://{bucket}/{key}"))?;
+            let contents = object.body.collect().await?.into_bytes();
+
+            if let Some(parent) = local_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&local_path, contents)?;
+            downloaded_files += 1;
+        }
+    }
+
+    info!("Downloaded {downloaded_files} configuration file(s)");
+
+    Ok(())
+}
+
+/// Checks that the path of an object stays inside the download directory,
+/// i.e. that it has no `..`, root or prefix component.
+fn local_relative_path(relative_path: &str) -> Result<&Path> {
+    let path = Path::new(relative_path);
+    if !path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!("`{relative_path}` is not a relative path inside the configuration prefix");
+    }
+
+    Ok(path)
+}
+
+impl FromStr for ConfigSource {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some(location) = value.strip_prefix(S3_SCHEME) else {
+            return Ok(ConfigSource::Local(PathBuf::from(value)));
+        };
+
+        let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+        if bucket.is_empty() {
+            return Err(format!("`{value}` has no bucket name"));
+        }
+
+        Ok(ConfigSource::S3 {
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Local(path) => write!(f, "{}", path.display()),
+            ConfigSource::S3 { bucket, prefix } => write!(f, "{S3_SCHEME}{bucket}/{prefix}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_local_source() {
+        assert_eq!(
+            "configuration_data".parse::<ConfigSource>(),
+            Ok(ConfigSource::Local(PathBuf::from("configuration_data")))
+        );
+    }
+
+    #[test]
+    fn test_parse_s3_source() {
+        assert_eq!(
+            "s3://my-bucket/configs/prod/".parse::<ConfigSource>(),
+            Ok(ConfigSource::S3 {
+                bucket: "my-bucket".to_string(),
+                prefix: "configs/prod".to_string(),
+            })
+        );
+        assert_eq!(
+            "s3://my-bucket".parse::<ConfigSource>(),
+            Ok(ConfigSource::S3 {
+                bucket: "my-bucket".to_string(),
+                prefix: String::new(),
+            })
+        );
+        assert!("s3:///configs".parse::<ConfigSource>().is_err());
+    }
+
+    #[test]
+    fn test_object_paths_must_stay_inside_the_download_directory() {
+        assert_eq!(
+            local_relative_path("validations/db-public.toml").unwrap(),
+            Path::new("validations/db-public.toml")
+        );
+        assert!(local_relative_path("../../etc/cron.d/job").is_err());
+        assert!(local_relative_path("validations/../../escape.toml").is_err());
+        assert!(local_relative_path("/etc/passwd").is_err());
+        assert!(local_relative_path("./common.toml").is_err());
+    }
+}
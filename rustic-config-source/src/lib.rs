@@ -0,0 +1,2 @@
+pub mod config_root;
+pub mod config_source;
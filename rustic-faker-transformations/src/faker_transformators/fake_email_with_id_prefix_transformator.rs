@@ -3,60 +3,70 @@ use fake::Fake;
 use fake::faker::internet::raw::SafeEmail;
 use fake::locales::EN;
 use polars::prelude::*;
-use rand::{SeedableRng, rngs::StdRng};
-use rand_seeder::SipHasher;
-use rustic_transformator::transformator::{
-    Transformator, combine_seeds, generate_seed_from_sip_rng,
-};
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator::{Transformator, string_column};
 use rustic_transformator::transformator_output::TransformatorOutput;
 use rustic_transformator::transformator_type::TransformatorType;
 
 #[derive(Builder)]
 pub struct FakeEmailWithIdPrefixTransformator {
     column_name: String,
+    /// The column whose value is used as the prefix of the fake email.
+    #[builder(default = String::from("id"))]
+    id_column: String,
+    /// The domain the fake emails are derived in, instead of the column.
+    domain: Option<String>,
+}
+
+impl FakeEmailWithIdPrefixTransformator {
+    fn domain(&self) -> &str {
+        self.domain.as_deref().unwrap_or(&self.column_name)
+    }
 }
 
 impl Transformator for FakeEmailWithIdPrefixTransformator {
-    fn transform(&self, input: &DataFrame, initial: &mut StdRng) -> Vec<TransformatorOutput> {
-        let user_id_series = &input
-            .column("id")
-            .expect("id not found")
-            .as_series()
-            .expect("id not found");
-
-        let user_email_iter = input
-            .column(&self.column_name)
-            .unwrap()
-            .str()
-            .unwrap()
-            .into_iter();
-
-        let user_id = user_id_series.i32().unwrap();
-
-        let transformed_values: Vec<Option<String>> = user_email_iter
-            .zip(user_id)
+    fn transform(
+        &self,
+        input: &DataFrame,
+        pseudonymizer: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
+        if self.id_column.trim().is_empty() {
+            return Err(TransformError::InvalidConfig {
+                message: format!("`id_column` of column `{}` is empty", self.column_name),
+            });
+        }
+
+        // Ids of any type, such as `Int64` or `String`, are used as they are printed.
+        let user_id = string_column(input, &self.id_column)?;
+        let user_email = string_column(input, &self.column_name)?;
+
+        let transformed_values: Vec<Option<String>> = user_email
+            .iter()
+            .zip(user_id.iter())
             .map(|(email, user_id)| {
-                let email = email.unwrap_or("");
-                let email_seed = &mut SipHasher::from(email).into_rng();
-                let email_seed = generate_seed_from_sip_rng(email_seed);
-                let email_seed = &mut StdRng::from_seed(email_seed);
-                let mut email_seed = combine_seeds(initial, email_seed);
-                let rng = &mut StdRng::from_rng(&mut email_seed);
+                // A row without an id cannot be prefixed, so its email is dropped.
+                let user_id = user_id?;
+                let rng = &mut pseudonymizer.value_rng(self.domain(), email);
                 let fake_email = SafeEmail(EN).fake_with_rng::<String, _>(rng);
-                Some(format!("{}-{}", user_id.unwrap(), fake_email))
+                Some(format!("{user_id}-{fake_email}"))
             })
             .collect();
 
         let transformed_series =
             StringChunked::new((&self.column_name).into(), transformed_values).into_series();
 
-        vec![TransformatorOutput {
+        Ok(vec![TransformatorOutput {
             column_name: self.column_name.to_string(),
             series: transformed_series,
-        }]
+        }])
     }
 
     fn transformator_type(&self) -> TransformatorType {
         TransformatorType::MultiColumn
     }
+
+    fn input_columns(&self) -> Vec<String> {
+        vec![self.id_column.clone()]
+    }
 }
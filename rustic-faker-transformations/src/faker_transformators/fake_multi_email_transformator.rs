@@ -1,51 +1,70 @@
 use bon::Builder;
 use polars::prelude::*;
-use rand::rngs::StdRng;
+use rustic_faker_types::FakerType;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
 use rustic_transformator::transformator_type::TransformatorType;
 
-use fake::{Fake, faker::internet::raw::SafeEmail, locales::EN};
-use rustic_transformator::transformator::Transformator;
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator::{Transformator, input_column, transform_values};
 use rustic_transformator::transformator_output::TransformatorOutput;
 
 #[derive(Builder)]
 pub struct FakeMultiEmailTransformator {
     column_name: String,
+    /// The domain the fake values are derived in, instead of the column.
+    domain: Option<String>,
+}
+
+impl FakeMultiEmailTransformator {
+    fn domain(&self) -> &str {
+        self.domain.as_deref().unwrap_or(&self.column_name)
+    }
+
+    /// Derives the fake value of every email on its own, so that an email gets the same
+    /// fake value as with `fake_email_transformation` in the same domain.
+    fn pseudonym(&self, pseudonymizer: &Pseudonymizer, email: Option<&str>) -> String {
+        pseudonymizer.pseudonym(self.domain(), &FakerType::Email, email)
+    }
 }
 
 impl Transformator for FakeMultiEmailTransformator {
-    fn transform(&self, input: &DataFrame, rng: &mut StdRng) -> Vec<TransformatorOutput> {
-        let column_values = input
-            .column(&self.column_name)
-            .unwrap()
-            .str()
-            .unwrap()
-            .iter()
-            .map(|value| {
-                if let Some(value) = value {
-                    if value.len() > 1 {
-                        let original_value_trimmed = &value[1..value.len() - 1];
-                        let updated_value = original_value_trimmed
-                            .split(',')
-                            .map(|_| SafeEmail(EN).fake_with_rng(rng))
-                            .collect::<Vec<String>>()
-                            .join(",");
-                        Some(format!("{{{updated_value}}}"))
-                    } else {
-                        Some(value.to_string())
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<Option<String>>>();
-
-        let transformed_series =
-            StringChunked::new((&self.column_name).into(), column_values).into_series();
-
-        vec![TransformatorOutput {
+    fn transform(
+        &self,
+        input: &DataFrame,
+        pseudonymizer: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
+        // The emails of a `List` column are its elements, the ones of a `String` column are
+        // formatted as a Postgres array, such as `{a@b.com,c@d.com}`.
+        let is_list = matches!(
+            input_column(input, &self.column_name)?.dtype(),
+            DataType::List(_)
+        );
+
+        let transformed_series = transform_values(input, &self.column_name, |value| {
+            if is_list {
+                return Some(self.pseudonym(pseudonymizer, value));
+            }
+            // A `NULL` value is faked as an array of a single email.
+            let Some(value) = value else {
+                return Some(format!("{{{}}}", self.pseudonym(pseudonymizer, None)));
+            };
+            if value.len() <= 1 {
+                return Some(value.to_string());
+            }
+
+            let original_value_trimmed = &value[1..value.len() - 1];
+            let updated_value = original_value_trimmed
+                .split(',')
+                .map(|email| self.pseudonym(pseudonymizer, Some(email.trim().trim_matches('"'))))
+                .collect::<Vec<String>>()
+                .join(",");
+            Some(format!("{{{updated_value}}}"))
+        })?;
+
+        Ok(vec![TransformatorOutput {
             column_name: self.column_name.to_string(),
             series: transformed_series,
-        }]
+        }])
     }
 
     fn transformator_type(&self) -> TransformatorType {
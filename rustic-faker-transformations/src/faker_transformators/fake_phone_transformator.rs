@@ -1,59 +1,64 @@
 use bon::Builder;
 use polars::prelude::*;
 use rand::Rng;
-use rand::rngs::StdRng;
-use rustic_transformator::transformator::Transformator;
+use rustic_faker_types::FakerType;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
+use rustic_transformator::transformator::{Transformator, transform_values};
 use rustic_transformator::transformator_output::TransformatorOutput;
 use rustic_transformator::transformator_type::TransformatorType;
 
 #[derive(Builder)]
 pub struct FakePhoneTransformator {
     column_name: String,
+    /// The domain the fake values are derived in, instead of the column.
+    domain: Option<String>,
+}
+
+impl FakePhoneTransformator {
+    fn domain(&self) -> &str {
+        self.domain.as_deref().unwrap_or(&self.column_name)
+    }
 }
 
 impl Transformator for FakePhoneTransformator {
-    fn transform(&self, input: &DataFrame, rng: &mut StdRng) -> Vec<TransformatorOutput> {
-        let column_values = input
-            .column(&self.column_name)
-            .unwrap()
-            .str()
-            .unwrap()
-            .iter()
-            .map(|value| {
-                if let Some(value) = value {
-                    if value.len() > 1 {
-                        let transformed: String = value
-                            .chars()
-                            .map(|c| {
-                                if c.is_ascii_digit() {
-                                    let original_digit = c.to_digit(10).unwrap();
-                                    let mut new_digit = original_digit;
-                                    while new_digit == original_digit {
-                                        new_digit = rng.random_range(0..10);
-                                    }
-                                    std::char::from_digit(new_digit, 10).unwrap()
-                                } else {
-                                    c
-                                }
-                            })
-                            .collect();
-                        Some(transformed)
+    fn transform(
+        &self,
+        input: &DataFrame,
+        pseudonymizer: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
+        let transformed_series = transform_values(input, &self.column_name, |value| {
+            // A `NULL` value has no digits to replace, so a whole phone number is derived.
+            let Some(value) = value else {
+                return Some(pseudonymizer.pseudonym(self.domain(), &FakerType::PhoneNumber, None));
+            };
+            if value.len() <= 1 {
+                return Some(value.to_string());
+            }
+
+            let rng = &mut pseudonymizer.value_rng(self.domain(), Some(value));
+            let transformed: String = value
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_digit() {
+                        let original_digit = c.to_digit(10).unwrap();
+                        let mut new_digit = original_digit;
+                        while new_digit == original_digit {
+                            new_digit = rng.random_range(0..10);
+                        }
+                        std::char::from_digit(new_digit, 10).unwrap()
                     } else {
-                        Some(value.to_string())
+                        c
                     }
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<Option<String>>>();
-
-        let transformed_series =
-            StringChunked::new((&self.column_name).into(), column_values).into_series();
+                })
+                .collect();
+            Some(transformed)
+        })?;
 
-        vec![TransformatorOutput {
+        Ok(vec![TransformatorOutput {
             column_name: self.column_name.to_string(),
             series: transformed_series,
-        }]
+        }])
     }
 
     fn transformator_type(&self) -> TransformatorType {
@@ -6,36 +6,53 @@ pub mod fake_phone_transformator;
 pub mod tests;
 
 use polars::prelude::*;
-use rand::rngs::StdRng;
 use rustic_faker_types::FakerType;
+use rustic_transformator::pseudonymizer::Pseudonymizer;
+use rustic_transformator::transform_error::TransformError;
 use rustic_transformator::transformator::Transformator;
 use rustic_transformator::transformator_output::TransformatorOutput;
+use rustic_transformator::transformator_registry::{TransformatorRegistry, string_param};
 use rustic_transformator::transformator_type::TransformatorType;
+use toml::Table;
+
+use crate::faker_transformators::fake_email_with_id_prefix_transformator::FakeEmailWithIdPrefixTransformator;
+use crate::faker_transformators::fake_multi_email_transformator::FakeMultiEmailTransformator;
+use crate::faker_transformators::fake_phone_transformator::FakePhoneTransformator;
 
 macro_rules! create_faker_transformator {
     ($struct_name:ident, $faker_type:expr) => {
         pub struct $struct_name {
             pub column_name: String,
-            pub retain_if_empty: bool,
+            /// The domain the fake values are derived in, instead of the column.
+            pub domain: Option<String>,
         }
 
         impl $struct_name {
-            pub fn new(column_name: impl Into<String>, retain_if_empty: bool) -> Self {
+            pub fn new(column_name: impl Into<String>) -> Self {
                 Self {
                     column_name: column_name.into(),
-                    retain_if_empty,
+                    domain: None,
                 }
             }
+
+            pub fn with_domain(mut self, domain: Option<String>) -> Self {
+                self.domain = domain;
+                self
+            }
         }
 
         impl Transformator for $struct_name {
-            fn transform(&self, input: &DataFrame, rng: &mut StdRng) -> Vec<TransformatorOutput> {
+            fn transform(
+                &self,
+                input: &DataFrame,
+                pseudonymizer: &Pseudonymizer,
+            ) -> Result<Vec<TransformatorOutput>, TransformError> {
                 self.transform_with_faker(
                     input,
                     &self.column_name,
-                    rng,
+                    self.domain.as_deref(),
+                    pseudonymizer,
                     $faker_type,
-                    self.retain_if_empty,
                 )
             }
 
@@ -53,3 +70,70 @@ create_faker_transformator!(FakeEmailTransformator, FakerType::Email);
 create_faker_transformator!(FakeFirstnameTransformator, FakerType::FirstName);
 create_faker_transformator!(FakeLastNameTransformator, FakerType::LastName);
 create_faker_transformator!(FakeMd5Transformator, FakerType::Md5);
+
+/// Registers every faker transformator, under its `operation_type` name.
+///
+/// Every faker accepts a `domain` string parameter.
+/// `fake_email_with_id_prefix_transformation` also accepts an `id_column` string parameter.
+pub fn register_transformators(registry: &mut TransformatorRegistry) {
+    macro_rules! register_faker_transformator {
+        ($name:literal, $struct_name:ident) => {
+            registry.register($name, |column_name, params| {
+                Ok(Box::new(
+                    $struct_name::new(column_name).with_domain(domain_param(params)?),
+                ))
+            });
+        };
+    }
+
+    register_faker_transformator!("fake_firstname_transformation", FakeFirstnameTransformator);
+    register_faker_transformator!("fake_lastname_transformation", FakeLastNameTransformator);
+    register_faker_transformator!("fake_name_transformation", FakeNameTransformator);
+    register_faker_transformator!("fake_email_transformation", FakeEmailTransformator);
+    register_faker_transformator!(
+        "fake_companyname_transformation",
+        FakeCompanyNameTransformator
+    );
+    register_faker_transformator!("fake_address_transformation", FakeAddressTransformator);
+    register_faker_transformator!("fake_md5_transformation", FakeMd5Transformator);
+
+    registry
+        .register("fake_phone_transformation", |column_name, params| {
+            Ok(Box::new(
+                FakePhoneTransformator::builder()
+                    .column_name(column_name.to_string())
+                    .maybe_domain(domain_param(params)?)
+                    .build(),
+            ))
+        })
+        .register("fake_multi_email_transformation", |column_name, params| {
+            Ok(Box::new(
+                FakeMultiEmailTransformator::builder()
+                    .column_name(column_name.to_string())
+                    .maybe_domain(domain_param(params)?)
+                    .build(),
+            ))
+        })
+        .register(
+            "fake_email_with_id_prefix_transformation",
+            |column_name, params| {
+                Ok(Box::new(
+                    FakeEmailWithIdPrefixTransformator::builder()
+                        .column_name(column_name.to_string())
+                        .maybe_id_column(string_param(params, "id_column")?.map(str::to_string))
+                        .maybe_domain(domain_param(params)?)
+                        .build(),
+                ))
+            },
+        );
+}
+
+/// Reads the `domain` parameter of a faker, which must not be blank when set.
+fn domain_param(params: &Table) -> Result<Option<String>, TransformError> {
+    match string_param(params, "domain")? {
+        Some(domain) if domain.trim().is_empty() => Err(TransformError::InvalidConfig {
+            message: "parameter `domain` must not be empty".to_string(),
+        }),
+        domain => Ok(domain.map(str::to_string)),
+    }
+}
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+
+    use polars::prelude::*;
+    use pretty_assertions::assert_eq;
+    use rustic_transformator::pseudonymizer::Pseudonymizer;
+    use rustic_transformator::transformator_registry::TransformatorRegistry;
+    use toml::Table;
+
+    use crate::faker_transformators::register_transformators;
+
+    fn test_df() -> DataFrame {
+        df!(
+            "id" => &[1i32, 2, 3, 4, 5, 1],
+            "a" => &[
+                Some("+30 210 1234567"),
+                Some("{ciri@cintra.com,geralt@kaer.morhen}"),
+                None,
+                Some("+44 20 7123 4567"),
+                Some("yennefer@vengerberg.com"),
+                Some("+30 210 1234567"),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn transformed(
+        registry: &TransformatorRegistry,
+        name: &str,
+        df: &DataFrame,
+    ) -> Vec<Option<String>> {
+        transformed_column(registry, name, "a", &Table::new(), df)
+    }
+
+    fn transformed_column(
+        registry: &TransformatorRegistry,
+        name: &str,
+        column_name: &str,
+        params: &Table,
+        df: &DataFrame,
+    ) -> Vec<Option<String>> {
+        let transformator = registry.build(name, column_name, params).unwrap();
+        let pseudonymizer = Pseudonymizer::seeded(42);
+
+        let transformed = transformator.transform(df, &pseudonymizer).unwrap();
+
+        transformed[0]
+            .series
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|value| value.map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_output_does_not_depend_on_the_row_order() {
+        let mut registry = TransformatorRegistry::new();
+        register_transformators(&mut registry);
+        let df = test_df();
+
+        for name in registry.names() {
+            let expected = transformed(&registry, name, &df);
+
+            let mut reversed = transformed(&registry, name, &df.reverse());
+            reversed.reverse();
+
+            assert_eq!(reversed, expected, "`{name}` depends on the row order");
+            assert_eq!(
+                expected[0], expected[5],
+                "`{name}` fakes the same value differently"
+            );
+        }
+    }
+
+    #[test]
+    fn test_output_does_not_depend_on_the_file_split() {
+        let mut registry = TransformatorRegistry::new();
+        register_transformators(&mut registry);
+        let df = test_df();
+
+        for name in registry.names() {
+            let expected = transformed(&registry, name, &df);
+
+            let mut split = transformed(&registry, name, &df.slice(0, 2));
+            split.extend(transformed(&registry, name, &df.slice(2, 1)));
+            split.extend(transformed(&registry, name, &df.slice(3, 3)));
+
+            assert_eq!(split, expected, "`{name}` depends on the file split");
+        }
+    }
+
+    #[test]
+    fn test_output_depends_on_the_column() {
+        let mut registry = TransformatorRegistry::new();
+        register_transformators(&mut registry);
+        let df = test_df();
+        let mut renamed = test_df();
+        renamed.rename("a", "b".into()).unwrap();
+
+        let transformator = registry
+            .build("fake_email_transformation", "b", &Table::new())
+            .unwrap();
+        let pseudonymizer = Pseudonymizer::seeded(42);
+        let other_column = transformator.transform(&renamed, &pseudonymizer).unwrap();
+
+        assert_ne!(
+            other_column[0]
+                .series
+                .str()
+                .unwrap()
+                .get(0)
+                .map(str::to_string),
+            transformed(&registry, "fake_email_transformation", &df)[0]
+        );
+    }
+
+    #[test]
+    fn test_output_does_not_depend_on_the_column_within_a_domain() {
+        let mut registry = TransformatorRegistry::new();
+        register_transformators(&mut registry);
+        let df = test_df();
+        let mut renamed = test_df();
+        renamed.rename("a", "b".into()).unwrap();
+        let params: Table = toml::from_str(r#"domain = "person.contact""#).unwrap();
+
+        for name in registry.names() {
+            assert_eq!(
+                transformed_column(&registry, name, "b", &params, &renamed),
+                transformed_column(&registry, name, "a", &params, &df),
+                "`{name}` depends on the column within a domain"
+            );
+        }
+    }
+
+    #[test]
+    fn test_blank_domains_are_rejected() {
+        let mut registry = TransformatorRegistry::new();
+        register_transformators(&mut registry);
+        let params: Table = toml::from_str(r#"domain = " ""#).unwrap();
+
+        for name in registry.names() {
+            let error = registry.build(name, "a", &params).err().unwrap();
+            assert_eq!(
+                error.to_string(),
+                "invalid configuration: parameter `domain` must not be empty"
+            );
+        }
+    }
+}
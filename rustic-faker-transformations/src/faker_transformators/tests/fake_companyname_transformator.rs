@@ -4,16 +4,16 @@ mod tests {
     use crate::faker_transformators::FakeCompanyNameTransformator;
     use polars::prelude::*;
     use pretty_assertions::assert_eq;
-    use rand::{SeedableRng, rngs::StdRng};
+    use rustic_transformator::pseudonymizer::Pseudonymizer;
     use rustic_transformator::transformator::Transformator;
 
     #[test]
     fn test_fake_company_name_transformator() {
         let df = DataFrame::new(vec![Series::new("a".into(), &["foo-bar"]).into()]).unwrap();
-        let transformator = FakeCompanyNameTransformator::new("a".to_string(), false);
-        let mut rng = StdRng::seed_from_u64(42);
+        let transformator = FakeCompanyNameTransformator::new("a".to_string());
+        let pseudonymizer = Pseudonymizer::seeded(42);
 
-        let transformed = transformator.transform(&df, &mut rng);
+        let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
 
         assert_eq!(transformed.len(), 1);
         assert_eq!(transformed[0].column_name, "a");
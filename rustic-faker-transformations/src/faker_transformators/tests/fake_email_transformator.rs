@@ -4,7 +4,8 @@ mod tests {
     use crate::faker_transformators::FakeEmailTransformator;
     use polars::prelude::*;
     use pretty_assertions::assert_eq;
-    use rand::{SeedableRng, rngs::StdRng};
+    use rustic_transformator::pseudonymizer::Pseudonymizer;
+    use rustic_transformator::transform_error::TransformError;
     use rustic_transformator::transformator::Transformator;
 
     #[test]
@@ -13,10 +14,10 @@ mod tests {
             Series::new("a".into(), &["foo-bar", "foo-qux"]).into(),
         ])
         .unwrap();
-        let transformator = FakeEmailTransformator::new("a".to_string(), false);
-        let mut rng = StdRng::seed_from_u64(42);
+        let transformator = FakeEmailTransformator::new("a".to_string());
+        let pseudonymizer = Pseudonymizer::seeded(42);
 
-        let transformed = transformator.transform(&df, &mut rng);
+        let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
 
         assert_eq!(transformed.len(), 1);
         assert_eq!(transformed[0].column_name, "a");
@@ -31,4 +32,51 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_fake_email_transformator_reads_columns_of_other_types() {
+        let df = DataFrame::new(vec![Series::new("a".into(), &[1i64, 2]).into()]).unwrap();
+        let pseudonymizer = Pseudonymizer::seeded(42);
+
+        let transformed = FakeEmailTransformator::new("a".to_string())
+            .transform(&df, &pseudonymizer)
+            .unwrap();
+
+        assert_eq!(transformed[0].series.dtype(), &DataType::String);
+        assert_eq!(transformed[0].series.null_count(), 0);
+    }
+
+    #[test]
+    fn test_fake_email_transformator_reports_unusable_columns() {
+        let nested = Series::new("a".into(), &[Series::new("".into(), &[1, 2])])
+            .implode()
+            .unwrap()
+            .into_series();
+        let df = DataFrame::new(vec![nested.into()]).unwrap();
+        let pseudonymizer = Pseudonymizer::seeded(42);
+
+        let error = FakeEmailTransformator::new("a".to_string())
+            .transform(&df, &pseudonymizer)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            TransformError::UnsupportedDtype {
+                column_name: "a".to_string(),
+                dtype: DataType::List(Box::new(DataType::Int32)),
+                expected: "str".to_string(),
+            }
+        );
+
+        let error = FakeEmailTransformator::new("b".to_string())
+            .transform(&df, &pseudonymizer)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            TransformError::MissingColumn {
+                column_name: "b".to_string()
+            }
+        );
+    }
 }
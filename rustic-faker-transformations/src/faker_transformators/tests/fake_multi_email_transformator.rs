@@ -1,20 +1,23 @@
 #[cfg(test)]
 mod tests {
 
+    use crate::faker_transformators::FakeEmailTransformator;
     use crate::faker_transformators::fake_multi_email_transformator::FakeMultiEmailTransformator;
     use polars::prelude::*;
     use pretty_assertions::assert_eq;
-    use rand::{SeedableRng, rngs::StdRng};
+    use rustic_transformator::pseudonymizer::Pseudonymizer;
     use rustic_transformator::transformator::Transformator;
 
     #[test]
     fn test_transform() {
-        let mut rng = StdRng::seed_from_u64(42);
+        let pseudonymizer = Pseudonymizer::seeded(42);
         let fake_multi_email_transformator = FakeMultiEmailTransformator::builder()
             .column_name("a".to_string())
             .build();
         let df = DataFrame::new(vec![Series::new("a".into(), &["foo, bar, qux"]).into()]).unwrap();
-        let transformed = fake_multi_email_transformator.transform(&df, &mut rng);
+        let transformed = fake_multi_email_transformator
+            .transform(&df, &pseudonymizer)
+            .unwrap();
 
         assert_eq!(transformed.len(), 1);
         assert_eq!(transformed[0].column_name, "a");
@@ -26,4 +29,88 @@ mod tests {
         assert_eq!(transformed_output.ends_with('}'), true);
         assert_eq!(transformed_output.split(',').collect::<Vec<_>>().len(), 3);
     }
+
+    #[test]
+    fn test_transform_list_column() {
+        let pseudonymizer = Pseudonymizer::seeded(42);
+        let fake_multi_email_transformator = FakeMultiEmailTransformator::builder()
+            .column_name("a".to_string())
+            .build();
+        let emails = Series::new("a".into(), &["g@kaer.morhen", "c@cintra.com"])
+            .implode()
+            .unwrap()
+            .into_series();
+        let df = DataFrame::new(vec![emails.into()]).unwrap();
+        let transformed = fake_multi_email_transformator
+            .transform(&df, &pseudonymizer)
+            .unwrap();
+
+        let emails = transformed[0]
+            .series
+            .list()
+            .unwrap()
+            .get_as_series(0)
+            .unwrap();
+        let emails = emails
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(emails.len(), 2);
+        assert!(
+            emails
+                .iter()
+                .all(|email| email.contains('@') && !email.contains('{'))
+        );
+        assert_ne!(emails, ["g@kaer.morhen", "c@cintra.com"]);
+    }
+
+    #[test]
+    fn test_emails_get_the_same_fake_values_as_fake_email_transformation() {
+        let pseudonymizer = Pseudonymizer::seeded(42);
+        let domain = Some("person.email".to_string());
+        let single_emails = DataFrame::new(vec![
+            Series::new("email".into(), &["g@kaer.morhen", "c@cintra.com"]).into(),
+        ])
+        .unwrap();
+        let fake_emails = FakeEmailTransformator::new("email")
+            .with_domain(domain.clone())
+            .transform(&single_emails, &pseudonymizer)
+            .unwrap();
+        let fake_emails = fake_emails[0]
+            .series
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>();
+
+        let multi_emails = DataFrame::new(vec![
+            Series::new(
+                "emails".into(),
+                &["{g@kaer.morhen,c@cintra.com}", "{c@cintra.com}"],
+            )
+            .into(),
+        ])
+        .unwrap();
+        let transformed = FakeMultiEmailTransformator::builder()
+            .column_name("emails".to_string())
+            .maybe_domain(domain)
+            .build()
+            .transform(&multi_emails, &pseudonymizer)
+            .unwrap();
+
+        assert_eq!(
+            transformed[0]
+                .series
+                .str()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            [
+                format!("{{{},{}}}", fake_emails[0], fake_emails[1]),
+                format!("{{{}}}", fake_emails[1]),
+            ]
+        );
+    }
 }
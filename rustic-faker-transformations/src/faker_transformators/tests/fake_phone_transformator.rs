@@ -3,7 +3,7 @@ mod tests {
 
     use polars::prelude::*;
     use pretty_assertions::assert_eq;
-    use rand::{SeedableRng, rngs::StdRng};
+    use rustic_transformator::pseudonymizer::Pseudonymizer;
     use rustic_transformator::transformator::Transformator;
 
     use crate::faker_transformators::fake_phone_transformator::FakePhoneTransformator;
@@ -18,9 +18,9 @@ mod tests {
         let transformator = FakePhoneTransformator::builder()
             .column_name("a".to_string())
             .build();
-        let mut rng = StdRng::seed_from_u64(42);
+        let pseudonymizer = Pseudonymizer::seeded(42);
 
-        let transformed = transformator.transform(&df, &mut rng);
+        let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
 
         assert_eq!(transformed.len(), 1);
         assert_eq!(transformed[0].column_name, "a");
@@ -35,4 +35,21 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn test_fake_phone_transformator_fakes_numeric_phones() {
+        let df = DataFrame::new(vec![Series::new("a".into(), &[442071234567i64]).into()]).unwrap();
+        let transformator = FakePhoneTransformator::builder()
+            .column_name("a".to_string())
+            .build();
+        let pseudonymizer = Pseudonymizer::seeded(42);
+
+        let transformed = transformator.transform(&df, &pseudonymizer).unwrap();
+
+        // The digits are faked one by one, so the anonymization can cast them back to `Int64`.
+        let phone = transformed[0].series.str().unwrap().get(0).unwrap();
+        assert_eq!(phone.len(), 12);
+        assert_ne!(phone, "442071234567");
+        assert!(phone.parse::<i64>().is_ok());
+    }
 }
@@ -1,4 +1,5 @@
 #[cfg(test)]
+pub mod deterministic_output;
 pub mod fake_address_transformator;
 pub mod fake_companyname_transformator;
 pub mod fake_email_transformator;
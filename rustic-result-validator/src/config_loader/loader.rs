@@ -2,11 +2,13 @@ use bon::Builder;
 use tracing::info;
 
 use crate::config_structs::root_struct::Validations;
-use std::{env, fs, path::PathBuf};
+use rustic_config_source::config_root::ConfigRoot;
+use std::fs;
 
 /// A struct for loading validation configurations.
 #[derive(Builder)]
 pub struct ValidationConfigLoader {
+    config_root: ConfigRoot,
     database_name: String,
     schema_name: String,
 }
@@ -28,11 +30,9 @@ impl ValidationConfigLoader {
     /// let validations = loader.load_validations_config();
     /// ```
     pub fn load_validations_config(&self) -> Validations {
-        let mut conf_file_path = PathBuf::new();
-        conf_file_path.push(env::current_dir().unwrap());
-        conf_file_path.push("configuration_data");
-        conf_file_path.push("validations");
-        conf_file_path.push(format!("{}-{}.toml", self.database_name, self.schema_name));
+        let conf_file_path = self
+            .config_root
+            .validations_config_path(&self.database_name, &self.schema_name);
 
         info!("Configuration file path: {:?}", conf_file_path.as_os_str());
 
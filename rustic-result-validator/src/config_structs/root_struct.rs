@@ -8,3 +8,34 @@ use super::validation_struct::ValidationConfiguration;
 pub struct Validations {
     pub validations: Vec<ValidationConfiguration>,
 }
+
+impl Validations {
+    /// Validates the validations configuration without executing any query.
+    ///
+    /// # Returns
+    ///
+    /// A description of every problem found. An empty `Vec` means that
+    /// the configuration is valid.
+    pub fn validate(&self) -> Vec<String> {
+        self.validations
+            .iter()
+            .enumerate()
+            .flat_map(|(index, validation)| {
+                let table = if validation.table.trim().is_empty() {
+                    format!("validation #{}", index + 1)
+                } else {
+                    format!("table `{}`", validation.table)
+                };
+
+                [
+                    ("table", &validation.table),
+                    ("query", &validation.query),
+                    ("column_to_check", &validation.column_to_check),
+                ]
+                .into_iter()
+                .filter(|(_, value)| value.trim().is_empty())
+                .map(move |(field, _)| format!("{table}: `{field}` must not be empty"))
+            })
+            .collect()
+    }
+}
@@ -12,6 +12,7 @@ use crate::config_structs::{root_struct::Validations, value_check_type_struct::V
 /// - `schema_name`: The name of the schema to validate.
 /// - `target_db_pool`: The connection pool for the target database.
 /// - `validations`: The validations to perform.
+/// - `skip_validations`: Whether to skip the validations.
 ///
 /// # Example
 ///
@@ -37,6 +38,8 @@ pub struct ResultValidator {
     schema_name: String,
     target_db_pool: Pool,
     validations: Validations,
+    #[builder(default)]
+    skip_validations: bool,
 }
 
 impl ResultValidator {
@@ -64,9 +67,7 @@ impl ResultValidator {
     /// ```
     pub async fn validate(&self) {
         // Check if we will skip validations
-        let skip_validations = should_skip_validations();
-
-        if skip_validations {
+        if self.skip_validations {
             info!("Skipping validations");
             return;
         }
@@ -138,11 +139,3 @@ impl ResultValidator {
         }
     }
 }
-
-// Control if we will skip the validations
-fn should_skip_validations() -> bool {
-    std::env::var("SKIP_VALIDATIONS")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse()
-        .unwrap()
-}
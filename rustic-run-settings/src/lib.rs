@@ -0,0 +1,4 @@
+pub mod pseudonymization_key;
+pub mod run_settings;
+pub mod run_settings_error;
+pub mod setting_override;
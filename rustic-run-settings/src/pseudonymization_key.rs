@@ -0,0 +1,49 @@
+use std::fmt::Debug;
+use std::path::Path;
+
+use crate::run_settings_error::RunSettingsError;
+
+/// The env variable holding the secret key of the `keyed` pseudonymization.
+pub const PSEUDONYMIZATION_KEY_VAR: &str = "PSEUDONYMIZATION_KEY";
+
+/// The secret key of the `keyed` pseudonymization.
+///
+/// The key is never printed, neither in the logs nor with the settings.
+#[derive(Clone, PartialEq)]
+pub struct PseudonymizationKey(Vec<u8>);
+
+impl PseudonymizationKey {
+    pub fn new(key: &str) -> Self {
+        // A key written with `echo` ends with a newline, which is not part of the key.
+        Self(key.trim_end_matches(['\n', '\r']).as_bytes().to_vec())
+    }
+
+    /// Reads the key from a file.
+    pub fn from_file(path: &Path) -> Result<Self, RunSettingsError> {
+        std::fs::read_to_string(path)
+            .map(|key| Self::new(&key))
+            .map_err(|e| RunSettingsError::Read {
+                path: path.to_path_buf(),
+                source: e,
+            })
+    }
+
+    /// The key used when no key is supplied: the seed of the run, which is not a secret.
+    pub fn default_key(rng_seed: u64) -> Self {
+        Self::new(&rng_seed.to_string())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Debug for PseudonymizationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PseudonymizationKey(..)")
+    }
+}
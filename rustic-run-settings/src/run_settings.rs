@@ -0,0 +1,546 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::pseudonymization_key::{PSEUDONYMIZATION_KEY_VAR, PseudonymizationKey};
+use crate::run_settings_error::RunSettingsError;
+use crate::setting_override::SettingOverride;
+
+/// The names of every setting, which are also the keys of the settings file.
+/// The env variable of a setting is its name in upper case.
+pub const SETTING_NAMES: [&str; 18] = [
+    "record_reduction_enabled",
+    "record_reduction_mode",
+    "rng_seed",
+    "pseudonymization_mode",
+    "pseudonymization_key_file",
+    "allow_default_pseudonymization_key",
+    "lenient_schema_preflight",
+    "num_of_buffers",
+    "db_connect_timeout",
+    "db_max_pool_size",
+    "db_keep_alives",
+    "create_role_as_superuser",
+    "required_pg_extensions",
+    "create_extensions_in_schema",
+    "upload_anonymized_files",
+    "anonymized_bucket",
+    "skip_validations",
+    "post_import_sql_execution",
+];
+
+/// The settings controlling the behavior of a run, loaded once before the run starts.
+///
+/// The settings are layered, each layer overriding the previous one:
+/// the defaults, the settings file, the env variables and the `--set` CLI flags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunSettings {
+    /// Whether to reduce the number of records or export all of them.
+    pub record_reduction_enabled: bool,
+    /// How the reduced records are kept consistent with each other.
+    pub record_reduction_mode: RecordReductionMode,
+    /// The randomized seed for anonymization.
+    pub rng_seed: u64,
+    /// How the randomness of the fake values is derived from the original values.
+    pub pseudonymization_mode: PseudonymizationMode,
+    /// The file holding the secret key of the `keyed` pseudonymization,
+    /// unless the `PSEUDONYMIZATION_KEY` env variable is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pseudonymization_key_file: Option<PathBuf>,
+    /// Whether the `keyed` pseudonymization may fall back to the default key, derived from
+    /// the `rng_seed`, when no secret key is supplied. Only meant for local runs.
+    pub allow_default_pseudonymization_key: bool,
+    /// Whether a configured table or column missing from the source schema only logs a warning.
+    pub lenient_schema_preflight: bool,
+    /// Number of concurrent threads anonymizing Parquet files.
+    pub num_of_buffers: usize,
+    /// Timeout for database connections, in seconds.
+    pub db_connect_timeout: u64,
+    /// The max pool size for database connections.
+    pub db_max_pool_size: usize,
+    /// Whether to keep alive database connections.
+    pub db_keep_alives: bool,
+    /// Whether to create the data import role as `superuser` in the target database.
+    pub create_role_as_superuser: bool,
+    /// The extensions to create in the target database.
+    pub required_pg_extensions: Vec<String>,
+    /// Whether to create the extensions in the schema, instead of the public one.
+    pub create_extensions_in_schema: bool,
+    /// Whether to upload anonymized files to the anonymized bucket.
+    pub upload_anonymized_files: bool,
+    /// Name of the S3 bucket containing anonymized Parquet files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymized_bucket: Option<String>,
+    /// Whether to skip validations after data export.
+    pub skip_validations: bool,
+    /// Whether to execute custom SQL queries after data import.
+    pub post_import_sql_execution: bool,
+    /// The secret key of the `keyed` pseudonymization, loaded along with the settings.
+    #[serde(skip)]
+    pseudonymization_key: Option<PseudonymizationKey>,
+}
+
+/// How the reduced records are kept consistent with each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordReductionMode {
+    /// Every table is reduced on its own.
+    #[default]
+    Independent,
+    /// The rows referencing rows that were not kept are also dropped,
+    /// following the foreign keys of the source schema.
+    ForeignKeys,
+}
+
+/// How the randomness of the fake values is derived from the original values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PseudonymizationMode {
+    /// From the `rng_seed`, which anyone can use to recompute the fake value of
+    /// a candidate original value.
+    #[default]
+    Seeded,
+    /// From a secret key, through HMAC-SHA256.
+    Keyed,
+}
+
+impl Default for RunSettings {
+    fn default() -> Self {
+        Self {
+            record_reduction_enabled: false,
+            record_reduction_mode: RecordReductionMode::default(),
+            rng_seed: 42,
+            pseudonymization_mode: PseudonymizationMode::default(),
+            pseudonymization_key_file: None,
+            allow_default_pseudonymization_key: false,
+            lenient_schema_preflight: false,
+            num_of_buffers: 80,
+            db_connect_timeout: 180,
+            db_max_pool_size: 24,
+            db_keep_alives: false,
+            create_role_as_superuser: false,
+            required_pg_extensions: vec![],
+            create_extensions_in_schema: false,
+            upload_anonymized_files: false,
+            anonymized_bucket: None,
+            skip_validations: false,
+            post_import_sql_execution: false,
+            pseudonymization_key: None,
+        }
+    }
+}
+
+impl RunSettings {
+    /// Loads the settings of a run, from every layer, and validates them.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings_file` - The TOML settings file, if any.
+    /// * `overrides` - The settings overridden through the `--set` CLI flag.
+    ///
+    /// # Returns
+    ///
+    /// The validated settings, or the first error found.
+    pub fn load(
+        settings_file: Option<&Path>,
+        overrides: &[SettingOverride],
+    ) -> Result<Self, RunSettingsError> {
+        let mut settings = match settings_file {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+
+        settings.apply_env_vars(|name| std::env::var(name).ok())?;
+        for setting_override in overrides {
+            settings.set(&setting_override.name, &setting_override.value)?;
+        }
+
+        settings.validate()?;
+        settings.load_pseudonymization_key(|name| std::env::var(name).ok())?;
+
+        Ok(settings)
+    }
+
+    /// Reads the settings file, falling back to the defaults for the missing settings.
+    pub fn from_file(path: &Path) -> Result<Self, RunSettingsError> {
+        let content = std::fs::read_to_string(path).map_err(|e| RunSettingsError::Read {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+
+        toml::from_str(&content).map_err(|e| RunSettingsError::Parse {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    /// Overrides every setting whose env variable is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `env_var` - Looks up an env variable by its name.
+    pub fn apply_env_vars(
+        &mut self,
+        env_var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), RunSettingsError> {
+        for name in SETTING_NAMES {
+            if let Some(value) = env_var(&name.to_uppercase()) {
+                self.set(name, &value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Overrides a single setting, parsing its value the same way for env variables and CLI flags.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), RunSettingsError> {
+        match name {
+            "record_reduction_enabled" => self.record_reduction_enabled = parse(name, value)?,
+            "record_reduction_mode" => self.record_reduction_mode = parse(name, value)?,
+            "rng_seed" => self.rng_seed = parse(name, value)?,
+            "pseudonymization_mode" => self.pseudonymization_mode = parse(name, value)?,
+            "pseudonymization_key_file" => {
+                self.pseudonymization_key_file =
+                    Some(PathBuf::from(value.trim())).filter(|path| !path.as_os_str().is_empty());
+            }
+            "allow_default_pseudonymization_key" => {
+                self.allow_default_pseudonymization_key = parse(name, value)?;
+            }
+            "lenient_schema_preflight" => self.lenient_schema_preflight = parse(name, value)?,
+            "num_of_buffers" => self.num_of_buffers = parse(name, value)?,
+            "db_connect_timeout" => self.db_connect_timeout = parse(name, value)?,
+            "db_max_pool_size" => self.db_max_pool_size = parse(name, value)?,
+            "db_keep_alives" => self.db_keep_alives = parse(name, value)?,
+            "create_role_as_superuser" => self.create_role_as_superuser = parse(name, value)?,
+            "required_pg_extensions" => {
+                self.required_pg_extensions = value
+                    .split(',')
+                    .map(|extension| extension.trim().to_string())
+                    .filter(|extension| !extension.is_empty())
+                    .collect();
+            }
+            "create_extensions_in_schema" => {
+                self.create_extensions_in_schema = parse(name, value)?;
+            }
+            "upload_anonymized_files" => self.upload_anonymized_files = parse(name, value)?,
+            "anonymized_bucket" => {
+                self.anonymized_bucket = Some(value.trim().to_string()).filter(|b| !b.is_empty());
+            }
+            "skip_validations" => self.skip_validations = parse(name, value)?,
+            "post_import_sql_execution" => self.post_import_sql_execution = parse(name, value)?,
+            _ => {
+                return Err(RunSettingsError::UnknownSetting {
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the settings against each other.
+    pub fn validate(&self) -> Result<(), RunSettingsError> {
+        let mut problems = vec![];
+
+        if self.num_of_buffers == 0 {
+            problems.push("`num_of_buffers` must be greater than 0".to_string());
+        }
+        if self.db_max_pool_size == 0 {
+            problems.push("`db_max_pool_size` must be greater than 0".to_string());
+        }
+        if self.upload_anonymized_files && self.anonymized_bucket.is_none() {
+            problems.push("`upload_anonymized_files` requires `anonymized_bucket`".to_string());
+        }
+        if self.allow_default_pseudonymization_key
+            && self.pseudonymization_mode != PseudonymizationMode::Keyed
+        {
+            problems.push(
+                "`allow_default_pseudonymization_key` requires the `keyed` `pseudonymization_mode`"
+                    .to_string(),
+            );
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(RunSettingsError::Invalid { problems })
+        }
+    }
+
+    /// Loads the secret key of the `keyed` pseudonymization, from the `PSEUDONYMIZATION_KEY`
+    /// env variable or else from the `pseudonymization_key_file`.
+    ///
+    /// A missing, empty or default key fails the run, unless `allow_default_pseudonymization_key`
+    /// is set: a warning is then logged and the default key is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `env_var` - Looks up an env variable by its name.
+    pub fn load_pseudonymization_key(
+        &mut self,
+        env_var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), RunSettingsError> {
+        if self.pseudonymization_mode != PseudonymizationMode::Keyed {
+            return Ok(());
+        }
+
+        let key = match env_var(PSEUDONYMIZATION_KEY_VAR) {
+            Some(key) => Some(PseudonymizationKey::new(&key)),
+            None => self
+                .pseudonymization_key_file
+                .as_deref()
+                .map(PseudonymizationKey::from_file)
+                .transpose()?,
+        };
+
+        match key {
+            Some(key)
+                if !key.is_empty() && key != PseudonymizationKey::default_key(self.rng_seed) =>
+            {
+                self.pseudonymization_key = Some(key);
+                Ok(())
+            }
+            _ if self.allow_default_pseudonymization_key => {
+                warn!(
+                    "No secret pseudonymization key was supplied, falling back to the default key: the fake values can be recomputed from the `rng_seed`"
+                );
+                Ok(())
+            }
+            _ => Err(RunSettingsError::Invalid {
+                problems: vec![format!(
+                    "the `keyed` `pseudonymization_mode` requires a non-default key, from `{PSEUDONYMIZATION_KEY_VAR}` or `pseudonymization_key_file`, unless `allow_default_pseudonymization_key` is set"
+                )],
+            }),
+        }
+    }
+
+    /// The key of the `keyed` pseudonymization, or `None` in the `seeded` mode.
+    ///
+    /// Falls back to the default key, derived from the `rng_seed`, when no key was loaded,
+    /// which `load_pseudonymization_key` only allows with `allow_default_pseudonymization_key`.
+    pub fn pseudonymization_key(&self) -> Option<PseudonymizationKey> {
+        match self.pseudonymization_mode {
+            PseudonymizationMode::Seeded => None,
+            PseudonymizationMode::Keyed => Some(
+                self.pseudonymization_key
+                    .clone()
+                    .unwrap_or_else(|| PseudonymizationKey::default_key(self.rng_seed)),
+            ),
+        }
+    }
+
+    /// Whether the records are reduced consistently with the foreign keys of the source schema.
+    pub fn is_foreign_key_consistent_reduction(&self) -> bool {
+        self.record_reduction_enabled
+            && self.record_reduction_mode == RecordReductionMode::ForeignKeys
+    }
+
+    pub fn db_connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.db_connect_timeout)
+    }
+
+    /// The settings in the format of the settings file.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("Run settings are always serializable")
+    }
+}
+
+impl FromStr for RecordReductionMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "independent" => Ok(RecordReductionMode::Independent),
+            "foreign_keys" => Ok(RecordReductionMode::ForeignKeys),
+            _ => Err("expected `independent` or `foreign_keys`".to_string()),
+        }
+    }
+}
+
+impl Display for RecordReductionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordReductionMode::Independent => write!(f, "independent"),
+            RecordReductionMode::ForeignKeys => write!(f, "foreign_keys"),
+        }
+    }
+}
+
+impl FromStr for PseudonymizationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "seeded" => Ok(PseudonymizationMode::Seeded),
+            "keyed" => Ok(PseudonymizationMode::Keyed),
+            _ => Err("expected `seeded` or `keyed`".to_string()),
+        }
+    }
+}
+
+impl Display for PseudonymizationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PseudonymizationMode::Seeded => write!(f, "seeded"),
+            PseudonymizationMode::Keyed => write!(f, "keyed"),
+        }
+    }
+}
+
+fn parse<T: FromStr>(name: &str, value: &str) -> Result<T, RunSettingsError>
+where
+    T::Err: Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e: T::Err| RunSettingsError::InvalidValue {
+            name: name.to_string(),
+            value: value.to_string(),
+            message: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_env_vars_override_the_settings_file() {
+        let mut settings: RunSettings = toml::from_str(
+            r#"
+            rng_seed = 7
+            num_of_buffers = 8
+            required_pg_extensions = ["postgis"]
+        "#,
+        )
+        .unwrap();
+        let env_vars = HashMap::from([
+            ("NUM_OF_BUFFERS", "16"),
+            ("RECORD_REDUCTION_MODE", "foreign_keys"),
+        ]);
+
+        settings
+            .apply_env_vars(|name| env_vars.get(name).map(|value| value.to_string()))
+            .unwrap();
+
+        assert_eq!(settings.rng_seed, 7);
+        assert_eq!(settings.num_of_buffers, 16);
+        assert_eq!(settings.required_pg_extensions, vec!["postgis"]);
+        assert_eq!(
+            settings.record_reduction_mode,
+            RecordReductionMode::ForeignKeys
+        );
+        assert!(!settings.is_foreign_key_consistent_reduction());
+    }
+
+    #[test]
+    fn test_malformed_values_name_the_setting() {
+        let mut settings = RunSettings::default();
+
+        let error = settings.set("rng_seed", "forty-two").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value `forty-two` for `rng_seed`: invalid digit found in string"
+        );
+
+        let error = settings.set("rng_sead", "42").unwrap_err();
+        assert_eq!(error.to_string(), "unknown setting `rng_sead`");
+    }
+
+    #[test]
+    fn test_inconsistent_settings_are_reported() {
+        let mut settings = RunSettings::default();
+        settings.set("upload_anonymized_files", "true").unwrap();
+        settings.set("num_of_buffers", "0").unwrap();
+
+        assert_eq!(
+            settings.validate().unwrap_err().to_string(),
+            "invalid settings: `num_of_buffers` must be greater than 0, \
+             `upload_anonymized_files` requires `anonymized_bucket`"
+        );
+    }
+
+    #[test]
+    fn test_printed_settings_round_trip() {
+        let mut settings = RunSettings::default();
+        settings
+            .set("required_pg_extensions", "postgis, pg_trgm")
+            .unwrap();
+        settings.set("anonymized_bucket", "anonymized").unwrap();
+
+        let printed = settings.to_toml();
+
+        assert_eq!(toml::from_str::<RunSettings>(&printed).unwrap(), settings);
+    }
+
+    #[test]
+    fn test_keyed_pseudonymization_refuses_a_missing_or_default_key() {
+        let mut settings = RunSettings::default();
+        settings.set("pseudonymization_mode", "keyed").unwrap();
+        settings.validate().unwrap();
+
+        for key in [None, Some(""), Some("42")] {
+            let error = settings
+                .clone()
+                .load_pseudonymization_key(|_| key.map(str::to_string))
+                .unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "invalid settings: the `keyed` `pseudonymization_mode` requires a non-default key, \
+                 from `PSEUDONYMIZATION_KEY` or `pseudonymization_key_file`, \
+                 unless `allow_default_pseudonymization_key` is set"
+            );
+        }
+
+        settings
+            .load_pseudonymization_key(|_| Some("kaer-morhen\n".to_string()))
+            .unwrap();
+        assert_eq!(
+            settings.pseudonymization_key().unwrap().as_bytes(),
+            b"kaer-morhen"
+        );
+    }
+
+    #[test]
+    fn test_default_pseudonymization_key_is_an_explicit_opt_in() {
+        let mut settings = RunSettings::default();
+        settings.set("pseudonymization_mode", "keyed").unwrap();
+        settings
+            .set("allow_default_pseudonymization_key", "true")
+            .unwrap();
+        settings.validate().unwrap();
+
+        settings.load_pseudonymization_key(|_| None).unwrap();
+
+        assert_eq!(
+            settings.pseudonymization_key(),
+            Some(PseudonymizationKey::default_key(42))
+        );
+
+        settings.set("pseudonymization_mode", "seeded").unwrap();
+        assert_eq!(
+            settings.validate().unwrap_err().to_string(),
+            "invalid settings: `allow_default_pseudonymization_key` requires the `keyed` `pseudonymization_mode`"
+        );
+    }
+
+    #[test]
+    fn test_pseudonymization_key_is_never_printed() {
+        let mut settings = RunSettings::default();
+        settings.set("pseudonymization_mode", "keyed").unwrap();
+        settings
+            .load_pseudonymization_key(|_| Some("kaer-morhen".to_string()))
+            .unwrap();
+
+        assert!(!settings.to_toml().contains("kaer-morhen"));
+        assert!(!format!("{settings:?}").contains("kaer-morhen"));
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// An error raised while loading the run settings.
+#[derive(Debug)]
+pub enum RunSettingsError {
+    /// The settings file could not be read.
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The settings file is not a valid settings file.
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// A setting, set through an env variable or a CLI flag, has a malformed value.
+    InvalidValue {
+        name: String,
+        value: String,
+        message: String,
+    },
+    /// A CLI flag refers to a setting that does not exist.
+    UnknownSetting { name: String },
+    /// The settings are well-formed, but inconsistent with each other.
+    Invalid { problems: Vec<String> },
+}
+
+impl Display for RunSettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunSettingsError::Read { path, source } => {
+                write!(f, "could not read {}: {source}", path.display())
+            }
+            RunSettingsError::Parse { path, source } => {
+                write!(
+                    f,
+                    "could not parse {}: {}",
+                    path.display(),
+                    source.to_string().trim_end()
+                )
+            }
+            RunSettingsError::InvalidValue {
+                name,
+                value,
+                message,
+            } => write!(f, "invalid value `{value}` for `{name}`: {message}"),
+            RunSettingsError::UnknownSetting { name } => write!(f, "unknown setting `{name}`"),
+            RunSettingsError::Invalid { problems } => {
+                write!(f, "invalid settings: {}", problems.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for RunSettingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RunSettingsError::Read { source, .. } => Some(source),
+            RunSettingsError::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::str::FromStr;
+
+/// A setting overridden through the `--set name=value` CLI flag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingOverride {
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for SettingOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected `name=value`, got `{s}`"))?;
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(format!("expected `name=value`, got `{s}`"));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+}
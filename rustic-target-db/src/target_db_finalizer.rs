@@ -1,12 +1,14 @@
 use bon::Builder;
 use colored::Colorize;
 use deadpool_postgres::Pool;
+use rustic_run_settings::run_settings::RunSettings;
 use std::env;
 use tracing::info;
 
 #[derive(Builder)]
 pub struct TargetDBFinalizer {
     pub target_db_pool: Pool,
+    pub run_settings: RunSettings,
 }
 
 impl TargetDBFinalizer {
@@ -112,7 +114,7 @@ impl TargetDBFinalizer {
     /// The queries are loaded from the {DATABASE_NAME}_{SCHEMA_NAME}_POST_IMPORT_SQL_QUERIES environment variable.
     /// Queries should be separated by semicolons.
     pub async fn execute_post_import_sql(&self, database_name: &str, schema_name: &str) {
-        if !should_execute_post_import_sql() {
+        if !self.run_settings.post_import_sql_execution {
             info!(
                 "{}",
                 "Post-import SQL execution is disabled, skipping..."
@@ -198,13 +200,6 @@ impl TargetDBFinalizer {
     }
 }
 
-fn should_execute_post_import_sql() -> bool {
-    env::var("POST_IMPORT_SQL_EXECUTION")
-        .unwrap_or_else(|_| String::from("false"))
-        .parse()
-        .unwrap()
-}
-
 fn post_import_sql_queries(database_name: &str, schema_name: &str) -> String {
     let env_var_name = format!(
         "{}_{}_POST_IMPORT_SQL_QUERIES",
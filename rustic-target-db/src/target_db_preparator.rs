@@ -6,6 +6,8 @@ use dms_cdc_operator::{
     cdc::snapshot_payload::CDCOperatorSnapshotPayload,
     postgres::{postgres_operator::PostgresOperator, postgres_operator_impl::PostgresOperatorImpl},
 };
+use rustic_config_source::config_root::ConfigRoot;
+use rustic_run_settings::run_settings::RunSettings;
 use rustic_shell::shell_command_executor::ShellCommandExecutor;
 use std::env;
 use tokio::fs;
@@ -18,6 +20,7 @@ const PG_DUMP_FILE_NAME: &str = "pg_dump.sql";
 pub struct TargetDbPreparator {
     pub source_db_pool: Pool,
     pub target_db_pool: Pool,
+    pub run_settings: RunSettings,
 }
 
 impl TargetDbPreparator {
@@ -65,7 +68,7 @@ impl TargetDbPreparator {
             .await
             .expect("Failed to drop schema");
 
-        let should_create_extensions = should_create_extensions_in_schema();
+        let should_create_extensions = self.run_settings.create_extensions_in_schema;
 
         info!("Should create extensions: {}", should_create_extensions);
 
@@ -73,7 +76,7 @@ impl TargetDbPreparator {
             return;
         }
 
-        let required_extensions = required_extensions();
+        let required_extensions = &self.run_settings.required_pg_extensions;
 
         info!("Required extensions: {:?}", required_extensions);
         if required_extensions.is_empty() {
@@ -142,7 +145,7 @@ impl TargetDbPreparator {
     ///
     pub async fn create_data_import_user(&self, schema_name: &str, target_username: &str) {
         let client = self.target_db_pool.get().await.unwrap();
-        let should_create_role_as_superuser = should_create_role_as_superuser();
+        let should_create_role_as_superuser = self.run_settings.create_role_as_superuser;
 
         // If the role should be created as a superuser, add the SUPERUSER keyword to the query
         // This is a case for the initial setup of the target database, when we are running
@@ -227,10 +230,13 @@ impl TargetDbPreparator {
 
     /// This was needed because not all of our live applications
     /// have properly setup their sequence ownership.
-    pub async fn fix_sequences_ownership(&self, database_name: &str, schema_name: &str) {
-        let fix_path = format!(
-            "configuration_data/sequences_fix/{database_name}-{schema_name}/ownerships.txt"
-        );
+    pub async fn fix_sequences_ownership(
+        &self,
+        config_root: &ConfigRoot,
+        database_name: &str,
+        schema_name: &str,
+    ) {
+        let fix_path = config_root.sequences_fix_path(database_name, schema_name);
 
         let sequence_ownership_fixes = match std::fs::read_to_string(fix_path) {
             Err(_) => vec![],
@@ -278,13 +284,6 @@ impl TargetDbPreparator {
     }
 }
 
-fn should_create_role_as_superuser() -> bool {
-    std::env::var("CREATE_ROLE_AS_SUPERUSER")
-        .unwrap_or_else(|_| String::from("false"))
-        .parse()
-        .unwrap()
-}
-
 fn superuser_username() -> String {
     std::env::var("SUPERUSER_URL")
         .expect("SUPERUSER_URL not set")
@@ -306,18 +305,3 @@ fn superuser_password() -> String {
         .collect::<Vec<_>>()[0]
         .to_string()
 }
-
-fn required_extensions() -> Vec<String> {
-    std::env::var("REQUIRED_PG_EXTENSIONS")
-        .unwrap_or_else(|_| String::from(""))
-        .split(',')
-        .map(|ext| ext.trim().to_string())
-        .collect()
-}
-
-fn should_create_extensions_in_schema() -> bool {
-    std::env::var("CREATE_EXTENSIONS_IN_SCHEMA")
-        .unwrap_or_else(|_| String::from("false"))
-        .parse()
-        .unwrap()
-}
@@ -1,3 +1,6 @@
+pub mod pseudonymizer;
+pub mod transform_error;
 pub mod transformator;
 pub mod transformator_output;
+pub mod transformator_registry;
 pub mod transformator_type;
@@ -0,0 +1,193 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use fake::{
+    Fake,
+    faker::{
+        address::raw::{CityName, PostCode, StreetName, ZipCode},
+        company::raw::CompanyName,
+        internet::raw::SafeEmail,
+        name::raw::{FirstName, LastName, Name},
+        phone_number::raw::PhoneNumber,
+    },
+    locales::EN,
+    uuid::UUIDv4,
+};
+use hmac::{Hmac, Mac};
+use rand::{SeedableRng, rngs::StdRng};
+use rand_seeder::SipHasher;
+use rustic_faker_types::FakerType;
+use sha2::Sha256;
+
+use crate::transformator::{combine_seeds, generate_seed_from_sip_rng};
+
+/// Derives the randomness of every fake value from its original value, so the same
+/// original value always gets the same fake value.
+///
+/// The randomness is scoped by a domain, such as `person.email`, which defaults to the name of
+/// the transformed column. The same original value gets the same fake value in every column,
+/// table or store sharing its domain, so tools other than the snapshots, such as
+/// `rustic-mongo-buddy`, can build the same pseudonymizer and call `pseudonym` to produce
+/// matching values.
+///
+/// ```
+/// use rustic_faker_types::FakerType;
+/// use rustic_transformator::pseudonymizer::Pseudonymizer;
+///
+/// // The same key as the `keyed` snapshots, so the emails match the ones of every column
+/// // configured with `domain = "person.email"`.
+/// let pseudonymizer = Pseudonymizer::keyed(b"secret");
+/// let fake_email = pseudonymizer.pseudonym("person.email", &FakerType::Email, Some("ciri@cintra.com"));
+/// ```
+#[derive(Clone)]
+pub enum Pseudonymizer {
+    /// The randomness is derived from the seed of the run, through SipHash.
+    ///
+    /// Anyone knowing the seed can compute the fake value of a candidate original value.
+    Seeded { seed: u64 },
+    /// The randomness is derived from a secret key, through HMAC-SHA256.
+    Keyed { key: Arc<[u8]> },
+}
+
+impl Pseudonymizer {
+    pub fn seeded(seed: u64) -> Self {
+        Pseudonymizer::Seeded { seed }
+    }
+
+    pub fn keyed(key: &[u8]) -> Self {
+        Pseudonymizer::Keyed { key: key.into() }
+    }
+
+    /// Returns a random number generator that does not depend on any value,
+    /// for transformators that are not derived from the original values.
+    pub fn rng(&self) -> StdRng {
+        match self {
+            Pseudonymizer::Seeded { seed } => StdRng::seed_from_u64(*seed),
+            Pseudonymizer::Keyed { key } => StdRng::from_seed(hmac_sha256(key, &[])),
+        }
+    }
+
+    /// Derives the random number generator of a single value, from its domain and the
+    /// original value.
+    ///
+    /// The derived RNG does not depend on the position of the value, on the other values,
+    /// or on how the table is split into files.
+    pub fn value_rng(&self, domain: &str, value: Option<&str>) -> StdRng {
+        match self {
+            Pseudonymizer::Seeded { seed } => {
+                let value_seed = &mut SipHasher::from((domain, value)).into_rng();
+                let value_seed = generate_seed_from_sip_rng(value_seed);
+                let value_seed = &mut StdRng::from_seed(value_seed);
+                let mut value_seed = combine_seeds(&mut StdRng::seed_from_u64(*seed), value_seed);
+                StdRng::from_rng(&mut value_seed)
+            }
+            Pseudonymizer::Keyed { key } => {
+                StdRng::from_seed(hmac_sha256(key, &[domain.as_bytes(), &value_bytes(value)]))
+            }
+        }
+    }
+
+    /// Generates the fake value of an original value, in the specified domain.
+    pub fn pseudonym(&self, domain: &str, faker_type: &FakerType, value: Option<&str>) -> String {
+        generate_fake_value_with_rng(faker_type, &mut self.value_rng(domain, value))
+    }
+}
+
+/// Generates a fake value using the specified faker type and RNG.
+fn generate_fake_value_with_rng(faker: &FakerType, rng: &mut StdRng) -> String {
+    match faker {
+        FakerType::FirstName => FirstName(EN).fake_with_rng::<String, _>(rng),
+        FakerType::LastName => LastName(EN).fake_with_rng::<String, _>(rng),
+        FakerType::Name => Name(EN).fake_with_rng::<String, _>(rng),
+        FakerType::CompanyName => CompanyName(EN).fake_with_rng::<String, _>(rng),
+        FakerType::Email => SafeEmail(EN).fake_with_rng::<String, _>(rng),
+        FakerType::PhoneNumber => PhoneNumber(EN).fake_with_rng::<String, _>(rng),
+        FakerType::Address => {
+            let city_name = CityName(EN).fake_with_rng::<String, _>(rng);
+            let post_code = PostCode(EN).fake_with_rng::<String, _>(rng);
+            let street_name = StreetName(EN).fake_with_rng::<String, _>(rng);
+            let zip_code = ZipCode(EN).fake_with_rng::<String, _>(rng);
+            format!("{street_name} {zip_code} {city_name} {post_code}")
+        }
+        FakerType::Md5 => UUIDv4.fake_with_rng(rng),
+    }
+}
+
+impl Debug for Pseudonymizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pseudonymizer::Seeded { seed } => f.debug_struct("Seeded").field("seed", seed).finish(),
+            // The key is a secret, so it is never printed.
+            Pseudonymizer::Keyed { .. } => f.debug_struct("Keyed").finish_non_exhaustive(),
+        }
+    }
+}
+
+// A `NULL` value is told apart from an empty string.
+fn value_bytes(value: Option<&str>) -> Vec<u8> {
+    match value {
+        Some(value) => [&[1], value.as_bytes()].concat(),
+        None => vec![0],
+    }
+}
+
+// Every part is prefixed with its length, so that the parts cannot be shifted into each other.
+fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(&(part.len() as u64).to_be_bytes());
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use super::*;
+
+    fn draw(rng: &mut StdRng) -> u64 {
+        rng.next_u64()
+    }
+
+    #[test]
+    fn test_keyed_values_depend_on_the_key_column_and_value() {
+        let pseudonymizer = Pseudonymizer::keyed(b"secret");
+
+        let value = draw(&mut pseudonymizer.value_rng("email", Some("ciri@cintra.com")));
+
+        assert_eq!(
+            value,
+            draw(&mut pseudonymizer.value_rng("email", Some("ciri@cintra.com")))
+        );
+        assert_ne!(
+            value,
+            draw(&mut Pseudonymizer::keyed(b"other").value_rng("email", Some("ciri@cintra.com")))
+        );
+        assert_ne!(
+            value,
+            draw(&mut pseudonymizer.value_rng("contact", Some("ciri@cintra.com")))
+        );
+        assert_ne!(
+            draw(&mut pseudonymizer.value_rng("email", Some(""))),
+            draw(&mut pseudonymizer.value_rng("email", None))
+        );
+    }
+
+    #[test]
+    fn test_keyed_values_do_not_depend_on_the_seed() {
+        let mut seeded = Pseudonymizer::seeded(42).value_rng("email", Some("ciri@cintra.com"));
+        let mut keyed = Pseudonymizer::keyed(b"42").value_rng("email", Some("ciri@cintra.com"));
+
+        assert_ne!(draw(&mut seeded), draw(&mut keyed));
+    }
+
+    #[test]
+    fn test_keyed_debug_does_not_print_the_key() {
+        assert_eq!(
+            format!("{:?}", Pseudonymizer::keyed(b"secret")),
+            "Keyed { .. }"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+use polars::prelude::DataType;
+
+/// An error raised while transforming the columns of a Dataframe.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformError {
+    /// The column to transform, or a column it depends on, is missing from the Dataframe.
+    MissingColumn { column_name: String },
+    /// The column has a data type the transformator cannot handle.
+    UnsupportedDtype {
+        column_name: String,
+        dtype: DataType,
+        expected: String,
+    },
+    /// The transformator is configured in a way it cannot work with.
+    InvalidConfig { message: String },
+}
+
+impl TransformError {
+    /// The column the error refers to, if any.
+    pub fn column_name(&self) -> Option<&str> {
+        match self {
+            TransformError::MissingColumn { column_name }
+            | TransformError::UnsupportedDtype { column_name, .. } => Some(column_name),
+            TransformError::InvalidConfig { .. } => None,
+        }
+    }
+}
+
+impl Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::MissingColumn { column_name } => {
+                write!(f, "column `{column_name}` does not exist")
+            }
+            TransformError::UnsupportedDtype {
+                column_name,
+                dtype,
+                expected,
+            } => write!(
+                f,
+                "column `{column_name}` is of type {dtype}, but a {expected} column is expected"
+            ),
+            TransformError::InvalidConfig { message } => {
+                write!(f, "invalid configuration: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
@@ -1,109 +1,70 @@
 use std::time::Instant;
 
+use crate::pseudonymizer::Pseudonymizer;
+use crate::transform_error::TransformError;
 use crate::transformator_output::TransformatorOutput;
 use crate::transformator_type::TransformatorType;
-use fake::{
-    Fake,
-    faker::{
-        address::raw::{CityName, PostCode, StreetName, ZipCode},
-        company::raw::CompanyName,
-        internet::raw::SafeEmail,
-        name::raw::{FirstName, LastName, Name},
-        phone_number::raw::PhoneNumber,
-    },
-    locales::EN,
-    uuid::UUIDv4,
-};
 use polars::prelude::*;
 use rand::{RngCore as _, SeedableRng, rngs::StdRng};
-use rand_seeder::{SipHasher, SipRng};
+use rand_seeder::SipRng;
 use rustic_duration::beautify_duration;
 use rustic_faker_types::FakerType;
 use tracing::info;
 
-/// Generates a fake value using the specified faker type and RNG.
-fn generate_fake_value_with_rng(faker: &FakerType, rng: &mut StdRng) -> String {
-    match faker {
-        FakerType::FirstName => FirstName(EN).fake_with_rng::<String, _>(rng),
-        FakerType::LastName => LastName(EN).fake_with_rng::<String, _>(rng),
-        FakerType::Name => Name(EN).fake_with_rng::<String, _>(rng),
-        FakerType::CompanyName => CompanyName(EN).fake_with_rng::<String, _>(rng),
-        FakerType::Email => SafeEmail(EN).fake_with_rng::<String, _>(rng),
-        FakerType::PhoneNumber => PhoneNumber(EN).fake_with_rng::<String, _>(rng),
-        FakerType::Address => {
-            let city_name = CityName(EN).fake_with_rng::<String, _>(rng);
-            let post_code = PostCode(EN).fake_with_rng::<String, _>(rng);
-            let street_name = StreetName(EN).fake_with_rng::<String, _>(rng);
-            let zip_code = ZipCode(EN).fake_with_rng::<String, _>(rng);
-            format!("{street_name} {zip_code} {city_name} {post_code}")
-        }
-        FakerType::Md5 => UUIDv4.fake(),
-    }
-}
-
-/// Generates a fake value using the specified faker type and random number generator.
-fn generate_fake_value_without_rng(faker: &FakerType) -> String {
-    match faker {
-        FakerType::FirstName => FirstName(EN).fake::<String>(),
-        FakerType::LastName => LastName(EN).fake::<String>(),
-        FakerType::Name => Name(EN).fake::<String>(),
-        FakerType::CompanyName => CompanyName(EN).fake::<String>(),
-        FakerType::Email => SafeEmail(EN).fake::<String>(),
-        FakerType::PhoneNumber => PhoneNumber(EN).fake::<String>(),
-        FakerType::Address => {
-            let city_name = CityName(EN).fake::<String>();
-            let post_code = PostCode(EN).fake::<String>();
-            let street_name = StreetName(EN).fake::<String>();
-            let zip_code = ZipCode(EN).fake::<String>();
-            format!("{street_name} {zip_code} {city_name} {post_code}")
-        }
-        FakerType::Md5 => UUIDv4.fake(),
-    }
-}
-
 /// Represents a transformation operation.
 pub trait Transformator: Send + Sync {
-    /// Transforms the input DataFrame using the specified random number generator.
-    fn transform(&self, input: &DataFrame, rng: &mut StdRng) -> Vec<TransformatorOutput>;
+    /// Transforms the input DataFrame, deriving the randomness from the specified pseudonymizer.
+    ///
+    /// Returns an error, instead of panicking, when a column is missing or of an unsupported type.
+    fn transform(
+        &self,
+        input: &DataFrame,
+        pseudonymizer: &Pseudonymizer,
+    ) -> Result<Vec<TransformatorOutput>, TransformError>;
 
     /// Returns the type of transformation.
     fn transformator_type(&self) -> TransformatorType;
 
-    /// Transforms the input DataFrame by generating fake values for the specified column using the specified faker type and random number generator.
-    /// If `retain_if_empty` is true, the original values will be retained if they are empty or null.
+    /// Returns the polars expression computing the transformed values, when the transformation
+    /// does not need to materialize the values row by row.
+    ///
+    /// Only used for `SingleColumn` transformators: the expression is aliased to their column and
+    /// compiled, along with the filter of the table, into a single lazy query. Transformators
+    /// without an expression fall back to `transform`.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the input DataFrame.
+    fn transform_expr(&self, _schema: &Schema) -> Result<Option<Expr>, TransformError> {
+        Ok(None)
+    }
+
+    /// Returns the columns the transformator reads, besides the ones it transforms.
+    ///
+    /// Transformators without an expression run in parallel, on the same input, unless they
+    /// read other columns: those only run once the previous transformators are applied.
+    fn input_columns(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Transforms the input DataFrame by generating fake values for the specified column using the specified faker type and pseudonymizer.
+    ///
+    /// Every fake value is derived from its original value, through `Pseudonymizer::pseudonym`,
+    /// in the specified domain or else in the domain of the column.
     fn transform_with_faker(
         &self,
         input: &DataFrame,
         column_name: &str,
-        rng: &mut StdRng,
+        domain: Option<&str>,
+        pseudonymizer: &Pseudonymizer,
         faker_type: FakerType,
-        retain_if_empty: bool,
-    ) -> Vec<TransformatorOutput> {
+    ) -> Result<Vec<TransformatorOutput>, TransformError> {
         let start = Instant::now();
+        let domain = domain.unwrap_or(column_name);
 
-        let series: Vec<Option<String>> = input
-            .column(column_name)
-            .unwrap()
-            .str()
-            .unwrap()
-            .into_iter()
-            .map(|value| {
-                let rng = &mut rng.clone();
-                match value {
-                    Some(value) if retain_if_empty && value.is_empty() => Some(value.to_string()),
-                    Some(value) => {
-                        let value_seed = &mut SipHasher::from(value).into_rng();
-                        let value_seed = generate_seed_from_sip_rng(value_seed);
-                        let value_seed = &mut StdRng::from_seed(value_seed);
-                        let mut value_seed = combine_seeds(rng, value_seed);
-                        let rng = &mut StdRng::from_rng(&mut value_seed);
-                        Some(generate_fake_value_with_rng(&faker_type, rng))
-                    }
-                    None if retain_if_empty => value.map(|value| value.to_string()),
-                    _ => Some(generate_fake_value_without_rng(&faker_type)),
-                }
-            })
-            .collect::<Vec<_>>();
+        let series = transform_values(input, column_name, |value| {
+            Some(pseudonymizer.pseudonym(domain, &faker_type, value))
+        })?;
 
         let elapsed = start.elapsed();
 
@@ -112,13 +73,106 @@ pub trait Transformator: Send + Sync {
             beautify_duration(elapsed)
         );
 
-        vec![TransformatorOutput {
+        Ok(vec![TransformatorOutput {
             column_name: column_name.to_string(),
-            series: Series::new(column_name.into(), series),
-        }]
+            series,
+        }])
     }
 }
 
+/// Fetches a column of the input DataFrame.
+pub fn input_column<'a>(
+    input: &'a DataFrame,
+    column_name: &str,
+) -> Result<&'a Column, TransformError> {
+    input
+        .column(column_name)
+        .map_err(|_| TransformError::MissingColumn {
+            column_name: column_name.to_string(),
+        })
+}
+
+/// Fetches the type of a column of the input schema.
+pub fn input_dtype<'a>(
+    schema: &'a Schema,
+    column_name: &str,
+) -> Result<&'a DataType, TransformError> {
+    schema
+        .get(column_name)
+        .ok_or_else(|| TransformError::MissingColumn {
+            column_name: column_name.to_string(),
+        })
+}
+
+/// Fetches a column of the input DataFrame as strings.
+///
+/// Columns of other types, such as numbers, dates or binaries, are cast to `String`.
+pub fn string_column(
+    input: &DataFrame,
+    column_name: &str,
+) -> Result<StringChunked, TransformError> {
+    string_values(
+        column_name,
+        input_column(input, column_name)?.as_materialized_series(),
+    )
+}
+
+/// Maps every value of a column, as a string, to a new string.
+///
+/// The values of a `List` column are mapped one by one, into a `List` of strings. The values of
+/// the other columns are cast to strings first, like `string_column`. The returned series is a
+/// column of strings named after the input column: the anonymization casts it back to the type
+/// of the input column.
+pub fn transform_values(
+    input: &DataFrame,
+    column_name: &str,
+    f: impl Fn(Option<&str>) -> Option<String>,
+) -> Result<Series, TransformError> {
+    let column = input_column(input, column_name)?.as_materialized_series();
+
+    let series = match column.list() {
+        Ok(lists) => lists
+            .into_iter()
+            .map(|values| {
+                values
+                    .map(|values| map_values(column_name, &values, &f))
+                    .transpose()
+            })
+            .collect::<Result<ListChunked, _>>()?
+            .into_series(),
+        Err(_) => map_values(column_name, column, &f)?,
+    };
+
+    Ok(series.with_name(column_name.into()))
+}
+
+fn map_values(
+    column_name: &str,
+    values: &Series,
+    f: &impl Fn(Option<&str>) -> Option<String>,
+) -> Result<Series, TransformError> {
+    Ok(string_values(column_name, values)?
+        .iter()
+        .map(f)
+        .collect::<StringChunked>()
+        .into_series())
+}
+
+fn string_values(column_name: &str, values: &Series) -> Result<StringChunked, TransformError> {
+    let unsupported_dtype = || TransformError::UnsupportedDtype {
+        column_name: column_name.to_string(),
+        dtype: values.dtype().clone(),
+        expected: DataType::String.to_string(),
+    };
+
+    values
+        .cast(&DataType::String)
+        .map_err(|_| unsupported_dtype())?
+        .str()
+        .cloned()
+        .map_err(|_| unsupported_dtype())
+}
+
 pub fn generate_seed_from_sip_rng(initial_seed: &mut SipRng) -> [u8; 32] {
     let mut seed = [0u8; 32];
     initial_seed.fill_bytes(&mut seed);
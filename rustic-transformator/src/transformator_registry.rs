@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use toml::Table;
+
+use crate::transform_error::TransformError;
+use crate::transformator::Transformator;
+
+/// Builds a transformator from the column it transforms and its TOML parameters.
+///
+/// Whole table transformators are built with an empty column name.
+pub type TransformatorFactory =
+    Arc<dyn Fn(&str, &Table) -> Result<Box<dyn Transformator>, TransformError> + Send + Sync>;
+
+/// The transformators that can be referred to by name in the anonymization configuration.
+///
+/// Built-in transformators are registered the same way as the ones of downstream crates,
+/// so in-house transformators only need to be registered before the run starts.
+#[derive(Default, Clone)]
+pub struct TransformatorRegistry {
+    factories: BTreeMap<String, TransformatorFactory>,
+}
+
+impl TransformatorRegistry {
+    /// Creates a registry without any transformator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a named transformator factory.
+    /// A factory registered under an existing name replaces the previous one.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn(&str, &Table) -> Result<Box<dyn Transformator>, TransformError>
+        + Send
+        + Sync
+        + 'static,
+    ) -> &mut Self {
+        self.factories.insert(name.into(), Arc::new(factory));
+        self
+    }
+
+    /// Returns whether a transformator is registered under the specified name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Returns the names of every registered transformator, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Builds the transformator registered under the specified name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the transformator is registered under.
+    /// * `column_name` - The column to transform, empty for whole table transformators.
+    /// * `params` - The parameters of the transformator, as configured.
+    pub fn build(
+        &self,
+        name: &str,
+        column_name: &str,
+        params: &Table,
+    ) -> Result<Box<dyn Transformator>, TransformError> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| TransformError::InvalidConfig {
+                message: format!("no transformator is registered as `{name}`"),
+            })?;
+
+        factory(column_name, params)
+    }
+}
+
+/// Reads an optional string parameter of a transformator.
+pub fn string_param<'a>(params: &'a Table, name: &str) -> Result<Option<&'a str>, TransformError> {
+    params
+        .get(name)
+        .map(|value| {
+            value.as_str().ok_or_else(|| TransformError::InvalidConfig {
+                message: format!("parameter `{name}` must be a string"),
+            })
+        })
+        .transpose()
+}
+
+/// Reads an optional boolean parameter of a transformator.
+pub fn bool_param(params: &Table, name: &str) -> Result<Option<bool>, TransformError> {
+    params
+        .get(name)
+        .map(|value| {
+            value
+                .as_bool()
+                .ok_or_else(|| TransformError::InvalidConfig {
+                    message: format!("parameter `{name}` must be a boolean"),
+                })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pseudonymizer::Pseudonymizer;
+    use crate::transformator_output::TransformatorOutput;
+    use crate::transformator_type::TransformatorType;
+    use polars::prelude::DataFrame;
+
+    struct ColumnTransformator(String);
+
+    impl Transformator for ColumnTransformator {
+        fn transform(
+            &self,
+            _: &DataFrame,
+            _: &Pseudonymizer,
+        ) -> Result<Vec<TransformatorOutput>, TransformError> {
+            Ok(vec![])
+        }
+
+        fn transformator_type(&self) -> TransformatorType {
+            TransformatorType::SingleColumn {
+                column_name: self.0.clone(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_registered_factories_are_built_by_name() {
+        let mut registry = TransformatorRegistry::new();
+        registry.register("suffix", |column_name, params| {
+            let suffix = string_param(params, "suffix")?.unwrap_or_default();
+            Ok(Box::new(ColumnTransformator(format!(
+                "{column_name}{suffix}"
+            ))))
+        });
+
+        let params = toml::from_str::<Table>(r#"suffix = "_v2""#).unwrap();
+        let transformator = registry.build("suffix", "email", &params).unwrap();
+
+        assert!(matches!(
+            transformator.transformator_type(),
+            TransformatorType::SingleColumn { column_name } if column_name == "email_v2"
+        ));
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["suffix"]);
+    }
+
+    #[test]
+    fn test_unknown_names_and_malformed_params_are_reported() {
+        let mut registry = TransformatorRegistry::new();
+        registry.register("suffix", |_, params| {
+            string_param(params, "suffix")?;
+            Ok(Box::new(ColumnTransformator(String::new())))
+        });
+
+        let error = registry.build("prefix", "email", &Table::new()).err();
+        assert_eq!(
+            error.unwrap().to_string(),
+            "invalid configuration: no transformator is registered as `prefix`"
+        );
+
+        let params = toml::from_str::<Table>("suffix = 2").unwrap();
+        let error = registry.build("suffix", "email", &params).err();
+        assert_eq!(
+            error.unwrap().to_string(),
+            "invalid configuration: parameter `suffix` must be a string"
+        );
+    }
+}
@@ -1,5 +1,8 @@
+use std::sync::Arc;
+
 use rustic_base_transformations::noop_transformator::NoOpTransformator;
 use rustic_transformator::transformator::Transformator;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
 
 pub trait WholeTableTransformator {
     fn transform(&self, operation_type_raw: &str) -> Box<dyn Transformator>;
@@ -19,3 +22,54 @@ impl WholeTableTransformator for NoOpWholeTableTransformator {
         Box::new(NoOpTransformator::new())
     }
 }
+
+/// Registers the transformators of a whole table transformator, under the `transformation`
+/// names of the `Single` anonymization types they are built for.
+///
+/// # Arguments
+///
+/// * `registry` - The registry of the run.
+/// * `transformations` - The names the whole table transformator builds transformators for.
+/// * `whole_table_transformator` - Builds the transformator of every name.
+pub fn register_whole_table_transformator(
+    registry: &mut TransformatorRegistry,
+    transformations: &[&str],
+    whole_table_transformator: impl WholeTableTransformator + Send + Sync + 'static,
+) {
+    let whole_table_transformator = Arc::new(whole_table_transformator);
+    for transformation in transformations {
+        let whole_table_transformator = whole_table_transformator.clone();
+        let operation_type_raw = transformation.to_string();
+        registry.register(*transformation, move |_, _| {
+            Ok(whole_table_transformator.transform(&operation_type_raw))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustic_transformator::transformator_type::TransformatorType;
+    use toml::Table;
+
+    use super::*;
+
+    #[test]
+    fn test_whole_table_transformators_are_built_by_the_registry() {
+        let mut registry = TransformatorRegistry::new();
+
+        register_whole_table_transformator(
+            &mut registry,
+            &["audit_log_transformation"],
+            NoOpWholeTableTransformator::new(),
+        );
+
+        let transformator = registry
+            .build("audit_log_transformation", "", &Table::new())
+            .unwrap();
+        assert!(matches!(
+            transformator.transformator_type(),
+            TransformatorType::NoOp
+        ));
+        assert!(!registry.contains("other_transformation"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use rustic_anonymization_config::config_structs::anonymization_config::AnonymizationConfig;
+use rustic_config_source::config_root::ConfigRoot;
+use rustic_result_validator::config_structs::root_struct::Validations;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
+use serde::de::DeserializeOwned;
+
+const ANONYMIZATION_CONFIG_SUFFIX: &str = "-sync.toml";
+
+/// Validates every anonymization (`*-sync.toml` and `common.toml`) and validations configuration
+/// file of the configuration directory offline, and prints the problems found per file.
+///
+/// Every transformator of the anonymization files is also built through the registry,
+/// so unregistered names and invalid parameters are reported.
+///
+/// # Returns
+///
+/// The total number of problems found.
+pub fn validate_configuration_data(
+    config_root: &ConfigRoot,
+    registry: &TransformatorRegistry,
+) -> usize {
+    let configuration_data_dir = config_root.path();
+    let common_config_path = config_root.common_config_path();
+
+    let anonymization_files = match list_toml_files(configuration_data_dir) {
+        Ok(files) => files
+            .into_iter()
+            .filter(|path| {
+                path.to_string_lossy()
+                    .ends_with(ANONYMIZATION_CONFIG_SUFFIX)
+                    || *path == common_config_path
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => {
+            print_file_problems(
+                configuration_data_dir,
+                &[format!("could not read directory: {e}")],
+            );
+            return 1;
+        }
+    };
+
+    // Not every setup has validations, so a missing directory is fine.
+    let validation_files = list_toml_files(&config_root.validations_dir()).unwrap_or_default();
+
+    let mut total_problems = 0;
+
+    for path in &anonymization_files {
+        let problems = parse_file::<AnonymizationConfig>(path).map_or_else(
+            |e| vec![e],
+            |config| {
+                config
+                    .validate()
+                    .iter()
+                    .chain(&config.validate_transformators(registry))
+                    .map(|problem| problem.to_string())
+                    .collect()
+            },
+        );
+        total_problems += problems.len();
+        print_file_problems(path, &problems);
+    }
+
+    for path in &validation_files {
+        let problems = parse_file::<Validations>(path)
+            .map_or_else(|e| vec![e], |validations| validations.validate());
+        total_problems += problems.len();
+        print_file_problems(path, &problems);
+    }
+
+    let checked_files = anonymization_files.len() + validation_files.len();
+    let summary = format!("Checked {checked_files} file(s), found {total_problems} problem(s)");
+    if total_problems == 0 {
+        println!("{}", summary.bold().green());
+    } else {
+        println!("{}", summary.bold().red());
+    }
+
+    total_problems
+}
+
+/// Lists the `.toml` files of a directory, sorted by path.
+fn list_toml_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+fn parse_file<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("could not read file: {e}"))?;
+    toml::from_str(&contents)
+        .map_err(|e| format!("could not parse file: {}", e.to_string().trim_end()))
+}
+
+fn print_file_problems(path: &Path, problems: &[String]) {
+    let path = path.display().to_string();
+
+    if problems.is_empty() {
+        println!("{} {}", "✔".green(), path);
+        return;
+    }
+
+    println!("{} {}", "✘".red(), path.bold());
+    for problem in problems {
+        println!("    - {}", problem.replace('\n', "\n      "));
+    }
+}
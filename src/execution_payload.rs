@@ -1,4 +1,5 @@
 use bon::Builder;
+use rustic_cdc_operator::subset::SubsetSpec;
 
 /// Helper struct for usage with any payload that will not
 /// be relevant to the DMS CDC Operator and will only be needed
@@ -6,10 +7,16 @@ use bon::Builder;
 #[derive(Builder)]
 pub struct ExecutionPayload {
     target_application_users: Vec<String>,
+    /// The subset to extract, instead of the whole schema.
+    subset_spec: Option<SubsetSpec>,
 }
 
 impl ExecutionPayload {
     pub fn target_application_users(&self) -> Vec<String> {
         self.target_application_users.clone()
     }
+
+    pub fn subset_spec(&self) -> Option<&SubsetSpec> {
+        self.subset_spec.as_ref()
+    }
 }
@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use polars::io::SerReader as _;
+use polars::prelude::{AnyValue, DataFrame, ParquetReader};
+use rustic_anonymization_config::config_structs::anonymization_config::AnonymizationConfig;
+use rustic_anonymization_operator::anonymization_dataframe_operator::{
+    AnonymizationDataFrameOperator, pseudonymizer,
+};
+use rustic_anonymization_operator::dataframe_preview::{DataFramePreview, preview_dataframe};
+use rustic_run_settings::run_settings::RunSettings;
+use rustic_transformator::transformator_registry::TransformatorRegistry;
+use serde_json::{Map, Value, json};
+use tracing::{info, warn};
+
+const S3_SCHEME: &str = "s3://";
+
+/// Longer values are truncated in the `table` output.
+const MAX_VALUE_WIDTH: usize = 40;
+
+/// How the preview is printed.
+#[derive(Clone, Copy, ValueEnum)]
+pub enum PreviewFormat {
+    /// A before/after table per row, highlighting the changed columns.
+    Table,
+    /// A single JSON document, for tooling.
+    Json,
+}
+
+/// A Parquet file, either local or in S3.
+#[derive(Clone)]
+pub enum ParquetLocation {
+    Local(PathBuf),
+    S3 { bucket: String, key: String },
+}
+
+impl FromStr for ParquetLocation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some(location) = value.strip_prefix(S3_SCHEME) else {
+            return Ok(ParquetLocation::Local(PathBuf::from(value)));
+        };
+
+        match location.split_once('/') {
+            Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => {
+                Ok(ParquetLocation::S3 {
+                    bucket: bucket.to_string(),
+                    key: key.to_string(),
+                })
+            }
+            _ => Err(format!("`{value}` is not an `s3://bucket/key` URL")),
+        }
+    }
+}
+
+impl std::fmt::Display for ParquetLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParquetLocation::Local(path) => write!(f, "{}", path.display()),
+            ParquetLocation::S3 { bucket, key } => write!(f, "{S3_SCHEME}{bucket}/{key}"),
+        }
+    }
+}
+
+/// Anonymizes the first rows of a Parquet file of a table, the same way as a snapshot,
+/// and prints them before and after, without touching any database.
+///
+/// # Arguments
+///
+/// * `config_path` - The anonymization configuration file.
+/// * `table_name` - The table the Parquet file belongs to.
+/// * `location` - The Parquet file.
+/// * `num_of_rows` - The number of rows to preview.
+/// * `format` - How the preview is printed.
+pub async fn preview_parquet_file(
+    config_path: &Path,
+    table_name: &str,
+    location: &ParquetLocation,
+    num_of_rows: usize,
+    format: PreviewFormat,
+    registry: &TransformatorRegistry,
+    run_settings: &RunSettings,
+) -> Result<()> {
+    let config = std::fs::read_to_string(config_path)
+        .with_context(|| format!("could not read {}", config_path.display()))?;
+    let config: AnonymizationConfig = toml::from_str(&config)
+        .with_context(|| format!("could not parse {}", config_path.display()))?;
+
+    info!("Reading parquet file: {location}");
+    let df = read_parquet_file(location).await?;
+
+    // The columns of the file stand in for the columns of the source table.
+    let source_tables = HashMap::from([(
+        table_name.to_string(),
+        df.get_column_names()
+            .into_iter()
+            .map(|column_name| column_name.to_string())
+            .collect(),
+    )]);
+    let resolved_config =
+        AnonymizationDataFrameOperator::resolve_config(&config, &source_tables, registry)?;
+    let resolved_table_config = resolved_config.table(table_name);
+    if resolved_table_config.is_none() {
+        warn!(
+            "{table_name} is not anonymized by {}",
+            config_path.display()
+        );
+    }
+
+    let preview = preview_dataframe(
+        &df,
+        resolved_table_config,
+        &pseudonymizer(run_settings),
+        table_name,
+        &location.to_string(),
+        num_of_rows,
+    )?;
+
+    match format {
+        PreviewFormat::Table => print_table(&preview, table_name, location),
+        PreviewFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&preview_json(&preview, table_name, location))?
+        ),
+    }
+
+    Ok(())
+}
+
+async fn read_parquet_file(location: &ParquetLocation) -> Result<DataFrame> {
+    let bytes = match location {
+        ParquetLocation::Local(path) => {
+            std::fs::read(path).with_context(|| format!("could not read {}", path.display()))?
+        }
+        ParquetLocation::S3 { bucket, key } => {
+            let s3_client = rustic_s3_config::create_s3_client().await;
+            let object = s3_client
+                .get_object()
+                .bucket(bucket)
+                .key(key)
+                .send()
+                .await
+                .with_context(|| format!("could not download {location}"))?;
+            object.body.collect().await?.into_bytes().to_vec()
+        }
+    };
+
+    ParquetReader::new(Cursor::new(bytes))
+        .finish()
+        .with_context(|| format!("could not read {location} as Parquet"))
+}
+
+fn print_table(preview: &DataFramePreview, table_name: &str, location: &ParquetLocation) {
+    let changed_columns = if preview.changed_columns.is_empty() {
+        "none".to_string()
+    } else {
+        preview.changed_columns.join(", ")
+    };
+    println!(
+        "{} {location}: {} row(s), changed columns: {}",
+        table_name.bold(),
+        preview.rows.len(),
+        changed_columns.yellow()
+    );
+
+    let column_width = preview
+        .column_names
+        .iter()
+        .map(|column_name| column_name.chars().count())
+        .chain(["column".len()])
+        .max()
+        .unwrap_or_default();
+    let value_width = preview
+        .rows
+        .iter()
+        .flat_map(|row| row.before.iter().chain(row.after.iter().flatten()))
+        .map(|value| table_value(value).chars().count())
+        .chain(["before".len()])
+        .max()
+        .unwrap_or_default();
+
+    for row in &preview.rows {
+        println!();
+        let Some(after) = &row.after else {
+            println!(
+                "{}",
+                format!("Row {}: dropped by the filter", row.index).dimmed()
+            );
+            continue;
+        };
+
+        println!("{}", format!("Row {}", row.index).bold());
+        println!(
+            "  {:column_width$} │ {:value_width$} │ after",
+            "column", "before"
+        );
+        for ((column_name, before), after) in
+            preview.column_names.iter().zip(&row.before).zip(after)
+        {
+            let line = format!(
+                "  {column_name:column_width$} │ {:value_width$} │ {}",
+                table_value(before),
+                table_value(after)
+            );
+            if before != after {
+                println!("{}", line.yellow());
+            } else if preview.is_changed(column_name) {
+                println!("{line}");
+            } else {
+                println!("{}", line.dimmed());
+            }
+        }
+    }
+}
+
+fn table_value(value: &AnyValue) -> String {
+    let value = if value.is_null() {
+        "NULL".to_string()
+    } else {
+        value.str_value().into_owned()
+    };
+
+    if value.chars().count() > MAX_VALUE_WIDTH {
+        let truncated = value.chars().take(MAX_VALUE_WIDTH - 1).collect::<String>();
+        format!("{truncated}…")
+    } else {
+        value
+    }
+}
+
+fn preview_json(preview: &DataFramePreview, table_name: &str, location: &ParquetLocation) -> Value {
+    let row_json = |values: &[AnyValue]| {
+        preview
+            .column_names
+            .iter()
+            .zip(values)
+            .map(|(column_name, value)| (column_name.clone(), json_value(value)))
+            .collect::<Map<_, _>>()
+    };
+
+    let rows = preview
+        .rows
+        .iter()
+        .map(|row| {
+            json!({
+                "row": row.index,
+                "before": row_json(&row.before),
+                // `null` when the row is dropped by the filter.
+                "after": row.after.as_deref().map(row_json),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "table": table_name,
+        "file": location.to_string(),
+        "columns": preview.column_names,
+        "changed_columns": preview.changed_columns,
+        "rows": rows,
+    })
+}
+
+// Numbers and booleans are kept as such, every other value is printed as a string.
+fn json_value(value: &AnyValue) -> Value {
+    match value {
+        AnyValue::Null => Value::Null,
+        AnyValue::Boolean(value) => Value::from(*value),
+        AnyValue::Int32(value) => Value::from(*value),
+        AnyValue::Int64(value) => Value::from(*value),
+        AnyValue::UInt32(value) => Value::from(*value),
+        AnyValue::UInt64(value) => Value::from(*value),
+        AnyValue::Float32(value) => Value::from(*value),
+        AnyValue::Float64(value) => Value::from(*value),
+        value => Value::from(value.str_value().into_owned()),
+    }
+}